@@ -5,7 +5,8 @@ use crate::orders::create::{CreateOrderParams, CreateOrderResponse};
 use crate::orders::get::{list_orders, ListOrdersParams, ListOrdersResponse};
 use crate::orders::update::{update_order, UpdateOrderRequestBody};
 use crate::orders::Order;
-use crate::positions::{get_position, list_positions, ListPositionsResponse, Position};
+use crate::positions::{get_position, list_positions, list_positions_page, ListPositionsParams, ListPositionsResponse, Position};
+use crate::trades::{list_trades, list_trades_page, ListTradesParams, ListTradesResponse};
 use crate::{authentication, orders};
 use std::any::Any;
 use std::time::Duration;
@@ -14,6 +15,7 @@ use tokio::net::TcpStream;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use crate::instruments::{get_instrument, Instrument};
 use crate::websockets::connect_websocket;
+use crate::websockets::stream::{connect_activity_stream, ActivityStream};
 
 #[derive(Debug, Clone)]
 pub struct AsyncClient {
@@ -101,6 +103,18 @@ where
         list_positions(self).await
     }
 
+    async fn list_positions_page(&self, params: ListPositionsParams) -> Result<ListPositionsResponse, Error> {
+        list_positions_page(self, params).await
+    }
+
+    async fn list_trades(&self) -> Result<ListTradesResponse, Error> {
+        list_trades(self).await
+    }
+
+    async fn list_trades_page(&self, params: ListTradesParams) -> Result<ListTradesResponse, Error> {
+        list_trades_page(self, params).await
+    }
+
     async fn get_instrument(&self, symbol: &str) -> Result<Instrument, Error> {
         get_instrument(self, symbol).await
     }
@@ -108,4 +122,8 @@ where
     async fn connect_websocket(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, Error> {
         connect_websocket(&self).await
     }
+
+    async fn connect_activity_stream(&self) -> Result<ActivityStream, Error> {
+        connect_activity_stream(self).await
+    }
 }
@@ -4,7 +4,8 @@ use crate::orders::create::{CreateOrderParams, CreateOrderResponse};
 use crate::orders::get::{ListOrdersParams, ListOrdersResponse};
 use crate::orders::update::UpdateOrderRequestBody;
 use crate::orders::Order;
-use crate::positions::{ListPositionsResponse, Position};
+use crate::positions::{ListPositionsParams, ListPositionsResponse, Position};
+use crate::trades::{ListTradesParams, ListTradesResponse};
 use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -79,6 +80,8 @@ impl Default for ClientOptions {
 use async_trait::async_trait;
 use tokio_tungstenite::WebSocketStream;
 use crate::instruments;
+#[cfg(feature = "async")]
+use crate::websockets::ActivityStream;
 
 #[cfg(feature = "async")]
 #[async_trait]
@@ -113,9 +116,40 @@ pub trait AsyncClearstreetClient: Send + Sync {
 
     async fn list_positions(&self) -> Result<ListPositionsResponse, Error>;
 
+    /// One page of positions. The default only serves the first page, via `list_positions`.
+    async fn list_positions_page(&self, params: ListPositionsParams) -> Result<ListPositionsResponse, Error> {
+        match params.page_token {
+            None => self.list_positions().await,
+            Some(_) => Err(Error::new(ErrorType::InternalError, "Position pagination is not implemented for this client")),
+        }
+    }
+
+    /// One page of trades. Clients that cannot list trades return an error by default.
+    async fn list_trades_page(&self, _params: ListTradesParams) -> Result<ListTradesResponse, Error> {
+        Err(Error::new(ErrorType::InternalError, "list_trades is not implemented for this client"))
+    }
+
+    /// The first page of trades.
+    async fn list_trades(&self) -> Result<ListTradesResponse, Error> {
+        self.list_trades_page(ListTradesParams::default()).await
+    }
+
     async fn get_instrument(&self, symbol: &str )-> Result<instruments::Instrument, Error>;
 
     async fn connect_websocket(&self) -> Result<WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>, Error>;
+
+    /// Wraps `connect_websocket` in an [`ActivityStream`].
+    async fn connect_activity_stream(&self) -> Result<ActivityStream, Error> {
+        Ok(ActivityStream::new(self.connect_websocket().await?))
+    }
+
+    async fn create_orders(&self, orders: Vec<CreateOrderParams>) -> Vec<Result<CreateOrderResponse, Error>> {
+        crate::orders::batch::create_orders(self, orders, crate::orders::batch::DEFAULT_BATCH_CONCURRENCY).await
+    }
+
+    async fn cancel_orders(&self, order_ids: Vec<String>) -> Vec<Result<(), Error>> {
+        crate::orders::batch::cancel_orders(self, order_ids, crate::orders::batch::DEFAULT_BATCH_CONCURRENCY).await
+    }
 }
 
 #[cfg(feature = "sync")]
@@ -133,4 +167,14 @@ pub trait SyncClearstreetClient: Send + Sync {
     fn get_position(&self, symbol: &str) -> Result<Position, Error>;
     fn list_positions(&self) -> Result<ListPositionsResponse, Error>;
     fn connect_websocket(&self) -> Result<tungstenite::protocol::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>>, Error>;
+    /// Opens an [`ActivityIterator`](crate::websockets::ActivityIterator) with this client's credentials.
+    fn activity_iterator(&self, options: crate::websockets::ActivityIteratorOptions) -> Result<crate::websockets::ActivityIterator, Error>;
+
+    fn create_orders(&self, orders: Vec<CreateOrderParams>) -> Vec<Result<CreateOrderResponse, Error>> {
+        crate::orders::batch::create_orders_blocking(self, orders, crate::orders::batch::DEFAULT_BATCH_CONCURRENCY)
+    }
+
+    fn cancel_orders(&self, order_ids: Vec<String>) -> Vec<Result<(), Error>> {
+        crate::orders::batch::cancel_orders_blocking(self, order_ids, crate::orders::batch::DEFAULT_BATCH_CONCURRENCY)
+    }
 }
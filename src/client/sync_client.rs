@@ -6,6 +6,7 @@ use crate::orders::delete::{delete_all_orders_blocking, delete_order_blocking};
 use crate::orders::get::ListOrdersParams;
 use crate::positions::ListPositionsResponse;
 use crate::websockets::connect_websocket_blocking;
+use crate::websockets::iterator::{ActivityIterator, ActivityIteratorOptions};
 use crate::{authentication, orders, positions};
 use reqwest::{blocking};
 use std::any::Any;
@@ -97,4 +98,8 @@ impl SyncClearstreetClient for SyncClient {
     fn connect_websocket(&self) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, Error> {
         connect_websocket_blocking(self)
     }
+
+    fn activity_iterator(&self, options: ActivityIteratorOptions) -> Result<ActivityIterator, Error> {
+        ActivityIterator::connect(self, options)
+    }
 }
@@ -0,0 +1,540 @@
+use crate::client::AsyncClearstreetClient;
+use crate::emulation::{format_quantity, parse_quantity};
+use crate::error::{Error, ErrorType};
+use crate::orders::create::CreateOrderParams;
+use crate::orders::update::UpdateOrderRequestBody;
+use crate::orders::validation::validate_order;
+use crate::orders::{Order, OrderState};
+use crate::websockets::broadcast::{ActivityBroadcaster, ActivityFilter};
+use crate::websockets::payloads::{ActivityMessage, PayloadType};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    /// An entry whose fills place a take-profit and a stop-loss as an OCO pair.
+    Bracket,
+    /// Two working orders where fills on either reduce the other, which is canceled once
+    /// nothing is left.
+    OneCancelsOther,
+    /// A primary order whose fills place the secondary.
+    OneTriggersOther,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupStatus {
+    /// The trigger order is working and contingent orders are not placed yet.
+    Pending,
+    /// Contingent orders are working.
+    Active,
+    /// Every order is done and a contingent order filled.
+    Completed,
+    /// Canceled by the caller, or the orders closed without filling.
+    Canceled,
+    /// An order was rejected or could not be placed. Orders still working are left in place.
+    Failed(String),
+}
+
+impl GroupStatus {
+    pub fn is_done(&self) -> bool {
+        matches!(self, GroupStatus::Completed | GroupStatus::Canceled | GroupStatus::Failed(_))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupLeg {
+    /// The order as placed, or as last resized.
+    pub params: CreateOrderParams,
+    /// Set once the order has been placed.
+    pub order_id: Option<String>,
+    /// Latest known state of the placed order.
+    pub order: Option<Order>,
+    requested_quantity: f64,
+    cancel_requested: bool,
+}
+
+impl GroupLeg {
+    fn new(params: CreateOrderParams) -> Self {
+        Self {
+            requested_quantity: parse_quantity(&params.quantity),
+            params,
+            order_id: None,
+            order: None,
+            cancel_requested: false,
+        }
+    }
+
+    fn is_working(&self) -> bool {
+        self.order_id.is_some() && !self.is_done() && !self.cancel_requested
+    }
+
+    fn is_done(&self) -> bool {
+        self.order.as_ref().is_some_and(Order::is_terminal)
+    }
+
+    fn is_rejected(&self) -> bool {
+        self.order.as_ref().is_some_and(|order| order.state == OrderState::Rejected)
+    }
+
+    pub fn filled_quantity(&self) -> f64 {
+        self.order.as_ref().map(|order| parse_quantity(&order.filled_quantity)).unwrap_or_default()
+    }
+
+    /// Keeps `order` if it is newer than the one held.
+    fn update(&mut self, order: &Order) {
+        if self.order.as_ref().is_none_or(|held| order.version > held.version) {
+            self.order = Some(order.clone());
+        }
+    }
+
+    async fn place(&mut self, client: &dyn AsyncClearstreetClient) -> Result<String, Error> {
+        let response = client.create_order(self.params.clone()).await?;
+        self.order_id = Some(response.order_id.clone());
+        Ok(response.order_id)
+    }
+
+    /// Changes the total quantity of the placed order, keeping its prices.
+    async fn resize(&mut self, client: &dyn AsyncClearstreetClient, quantity: f64) -> Result<(), Error> {
+        let Some(order_id) = &self.order_id else {
+            return Ok(());
+        };
+
+        let quantity = format_quantity(quantity);
+        let params = UpdateOrderRequestBody {
+            quantity: quantity.clone(),
+            price: self.order.as_ref().map_or(self.params.price.clone(), |order| order.price.clone()),
+            stop_price: self.order.as_ref().map_or(self.params.stop_price.clone(), |order| order.stop_price.clone()),
+        };
+        client.update_order(order_id, params).await?;
+        self.params.quantity = quantity;
+        Ok(())
+    }
+
+    async fn cancel(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        if let Some(order_id) = &self.order_id {
+            if !self.is_done() && !self.cancel_requested {
+                client.delete_order(order_id).await?;
+                self.cancel_requested = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A set of orders placed and canceled together.
+#[derive(Debug, Clone)]
+pub struct OrderGroup {
+    pub group_id: String,
+    pub kind: GroupKind,
+    pub status: GroupStatus,
+    /// Placed first, contingent orders wait for it to fill. `None` for OCO groups.
+    pub trigger: Option<GroupLeg>,
+    pub contingent: Vec<GroupLeg>,
+}
+
+impl OrderGroup {
+    pub fn legs(&self) -> impl Iterator<Item = &GroupLeg> {
+        self.trigger.iter().chain(self.contingent.iter())
+    }
+
+    fn legs_mut(&mut self) -> impl Iterator<Item = &mut GroupLeg> {
+        self.trigger.iter_mut().chain(self.contingent.iter_mut())
+    }
+
+    fn leg_mut(&mut self, order_id: &str) -> Option<&mut GroupLeg> {
+        self.legs_mut().find(|leg| leg.order_id.as_deref() == Some(order_id))
+    }
+
+    fn fail(&mut self, reason: String) {
+        tracing::warn!("Order group {} failed: {}", self.group_id, reason);
+        self.status = GroupStatus::Failed(reason);
+    }
+
+    /// Quantity contingent leg `index` should have in total, given the fills so far.
+    ///
+    /// Exits cover what the trigger filled; in an OCO pair each leg covers its requested
+    /// quantity. Fills on the other legs of the pair come off that.
+    fn target_quantity(&self, index: usize) -> f64 {
+        let cover = match &self.trigger {
+            Some(trigger) => trigger.filled_quantity(),
+            None => self.contingent[index].requested_quantity,
+        };
+        if self.kind == GroupKind::OneTriggersOther {
+            return cover;
+        }
+
+        let filled_elsewhere: f64 = self
+            .contingent
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(_, leg)| leg.filled_quantity())
+            .sum();
+        (cover - filled_elsewhere).max(0.0)
+    }
+
+    /// Moves the group forward from the latest order states. Returns the ids of orders it placed.
+    ///
+    /// Contingent orders are placed once the trigger starts filling and resized as its fills
+    /// arrive. A fill on one leg of an OCO pair reduces the other to the unfilled remainder,
+    /// and the other is canceled once nothing remains and the filled leg is done. A
+    /// contingent order that finishes while the trigger is still working cancels the
+    /// trigger's remainder, since that order could no longer be resized.
+    async fn advance(&mut self, client: &dyn AsyncClearstreetClient) -> Vec<String> {
+        let mut placed = Vec::new();
+        if self.status.is_done() {
+            return placed;
+        }
+
+        if let Some(trigger) = &self.trigger {
+            if let Some(order) = trigger.order.as_ref().filter(|_| trigger.is_rejected()) {
+                self.fail(format!("Trigger order {} was rejected: {}", order.order_id, order.text));
+                return placed;
+            }
+
+            if trigger.filled_quantity() <= 0.0 {
+                if trigger.is_done() {
+                    self.status = GroupStatus::Canceled;
+                }
+                return placed;
+            }
+        }
+
+        for index in 0..self.contingent.len() {
+            let target = self.target_quantity(index);
+            let leg = &self.contingent[index];
+
+            if leg.order_id.is_none() {
+                if target <= 0.0 {
+                    continue;
+                }
+                self.contingent[index].params.quantity = format_quantity(target);
+                match self.contingent[index].place(client).await {
+                    Ok(order_id) => placed.push(order_id),
+                    Err(e) => {
+                        self.fail(format!("Could not place contingent order: {}", e));
+                        return placed;
+                    }
+                }
+                continue;
+            }
+
+            if !leg.is_working() {
+                continue;
+            }
+
+            if target <= leg.filled_quantity() {
+                let filled_sibling_done = self
+                    .contingent
+                    .iter()
+                    .enumerate()
+                    .any(|(other, sibling)| other != index && sibling.filled_quantity() > 0.0 && sibling.is_done());
+                if filled_sibling_done {
+                    if let Err(e) = self.contingent[index].cancel(client).await {
+                        tracing::warn!("Could not cancel order in group {}: {}", self.group_id, e);
+                    }
+                }
+            } else if target != parse_quantity(&leg.params.quantity) {
+                if let Err(e) = self.contingent[index].resize(client, target).await {
+                    tracing::warn!("Could not resize order in group {}: {}", self.group_id, e);
+                }
+            }
+        }
+
+        if self.contingent.iter().any(|leg| leg.order_id.is_some()) {
+            self.status = GroupStatus::Active;
+        }
+
+        if let Some(order) = self.contingent.iter().find(|leg| leg.is_rejected()).and_then(|leg| leg.order.as_ref()) {
+            let reason = format!("Contingent order {} was rejected: {}", order.order_id, order.text);
+            self.fail(reason);
+            return placed;
+        }
+
+        let contingent_done = self.contingent.iter().any(|leg| leg.order_id.is_some() && leg.is_done());
+        if let Some(trigger) = self.trigger.as_mut() {
+            if contingent_done && trigger.is_working() {
+                if let Err(e) = trigger.cancel(client).await {
+                    tracing::warn!("Could not cancel order in group {}: {}", self.group_id, e);
+                }
+            }
+        }
+
+        let trigger_done = self.trigger.as_ref().is_none_or(GroupLeg::is_done);
+        let contingent_done = self.contingent.iter().all(|leg| leg.order_id.is_none() || leg.is_done());
+        if trigger_done && contingent_done {
+            let any_filled = self.contingent.iter().any(|leg| leg.filled_quantity() > 0.0);
+            self.status = if any_filled { GroupStatus::Completed } else { GroupStatus::Canceled };
+        }
+
+        placed
+    }
+}
+
+/// A group whose status changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupUpdate {
+    pub group_id: String,
+    pub previous: GroupStatus,
+    pub current: GroupStatus,
+}
+
+/// Emulates bracket, one-cancels-other and one-triggers-other orders.
+///
+/// Groups are driven by `OrderUpdate` messages passed to [`OrderGroupManager::apply_message`],
+/// or by [`OrderGroupManager::run`]. After a reconnect, [`OrderGroupManager::resync`]
+/// re-queries every order in an unfinished group so fills missed while disconnected still
+/// trigger or cancel the rest of the group. Status changes are published to
+/// [`OrderGroupManager::subscribe`] receivers.
+pub struct OrderGroupManager {
+    groups: HashMap<String, OrderGroup>,
+    by_order_id: HashMap<String, String>,
+    next_group: u64,
+    updates: broadcast::Sender<GroupUpdate>,
+}
+
+impl Default for OrderGroupManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderGroupManager {
+    pub fn new() -> Self {
+        let (updates, _) = broadcast::channel(1024);
+
+        Self {
+            groups: HashMap::new(),
+            by_order_id: HashMap::new(),
+            next_group: 1,
+            updates,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<GroupUpdate> {
+        self.updates.subscribe()
+    }
+
+    pub fn group(&self, group_id: &str) -> Option<&OrderGroup> {
+        self.groups.get(group_id)
+    }
+
+    pub fn status(&self, group_id: &str) -> Option<&GroupStatus> {
+        self.groups.get(group_id).map(|group| &group.status)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = &OrderGroup> {
+        self.groups.values()
+    }
+
+    /// Places `entry`, then `take_profit` and `stop_loss` as an OCO pair sized to its fills so far.
+    pub async fn submit_bracket(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        entry: CreateOrderParams,
+        take_profit: CreateOrderParams,
+        stop_loss: CreateOrderParams,
+    ) -> Result<String, Error> {
+        self.submit(client, GroupKind::Bracket, Some(entry), vec![take_profit, stop_loss])
+            .await
+    }
+
+    /// Places both orders; fills on either reduce the other, and a completed fill cancels it.
+    pub async fn submit_oco(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        first: CreateOrderParams,
+        second: CreateOrderParams,
+    ) -> Result<String, Error> {
+        self.submit(client, GroupKind::OneCancelsOther, None, vec![first, second])
+            .await
+    }
+
+    /// Places `primary`, then `secondary` sized to its fills so far.
+    pub async fn submit_oto(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        primary: CreateOrderParams,
+        secondary: CreateOrderParams,
+    ) -> Result<String, Error> {
+        self.submit(client, GroupKind::OneTriggersOther, Some(primary), vec![secondary])
+            .await
+    }
+
+    async fn submit(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        kind: GroupKind,
+        trigger: Option<CreateOrderParams>,
+        contingent: Vec<CreateOrderParams>,
+    ) -> Result<String, Error> {
+        // Contingent orders are placed later, so surface their mistakes now.
+        for params in trigger.iter().chain(contingent.iter()) {
+            validate_order(params)?;
+        }
+
+        let group_id = format!("group-{}", self.next_group);
+        self.next_group += 1;
+
+        let mut group = OrderGroup {
+            group_id: group_id.clone(),
+            kind,
+            status: GroupStatus::Pending,
+            trigger: trigger.map(GroupLeg::new),
+            contingent: contingent.into_iter().map(GroupLeg::new).collect(),
+        };
+
+        // Nothing is working if the first order fails, so report it instead of a failed group.
+        let first = match group.trigger.as_mut() {
+            Some(trigger) => trigger,
+            None => &mut group.contingent[0],
+        };
+        let first_order_id = first.place(client).await?;
+        self.by_order_id.insert(first_order_id, group_id.clone());
+
+        let placed = if group.trigger.is_some() {
+            Vec::new()
+        } else {
+            group.advance(client).await
+        };
+        self.insert(group, placed);
+
+        Ok(group_id)
+    }
+
+    fn insert(&mut self, group: OrderGroup, placed: Vec<String>) {
+        for order_id in placed {
+            self.by_order_id.insert(order_id, group.group_id.clone());
+        }
+        self.groups.insert(group.group_id.clone(), group);
+    }
+
+    /// Records the latest state of a grouped order and acts on it.
+    pub async fn apply_order(&mut self, client: &dyn AsyncClearstreetClient, order: &Order) -> Option<GroupUpdate> {
+        let group_id = self.by_order_id.get(&order.order_id)?.clone();
+        let mut group = self.groups.remove(&group_id)?;
+
+        if let Some(leg) = group.leg_mut(&order.order_id) {
+            leg.update(order);
+        }
+
+        self.advance(client, group).await
+    }
+
+    pub async fn apply_message(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        message: &ActivityMessage,
+    ) -> Option<GroupUpdate> {
+        match message {
+            ActivityMessage::OrderUpdate(update) => self.apply_order(client, &update.payload.data).await,
+            _ => None,
+        }
+    }
+
+    async fn advance(&mut self, client: &dyn AsyncClearstreetClient, mut group: OrderGroup) -> Option<GroupUpdate> {
+        let previous = group.status.clone();
+        let placed = group.advance(client).await;
+
+        let update = (group.status != previous).then(|| GroupUpdate {
+            group_id: group.group_id.clone(),
+            previous,
+            current: group.status.clone(),
+        });
+        self.insert(group, placed);
+
+        if let Some(update) = &update {
+            // No receivers is not an error.
+            let _ = self.updates.send(update.clone());
+        }
+        update
+    }
+
+    /// Re-fetches every order in unfinished groups and acts on what changed.
+    pub async fn resync(&mut self, client: &dyn AsyncClearstreetClient) -> Result<Vec<GroupUpdate>, Error> {
+        let group_ids: Vec<String> = self
+            .groups
+            .values()
+            .filter(|group| !group.status.is_done())
+            .map(|group| group.group_id.clone())
+            .collect();
+
+        let mut updates = Vec::new();
+        for group_id in group_ids {
+            let Some(mut group) = self.groups.remove(&group_id) else {
+                continue;
+            };
+
+            let mut fetched = Ok(());
+            for leg in group.legs_mut() {
+                if let Some(order_id) = &leg.order_id {
+                    match client.get_order(order_id).await {
+                        Ok(order) => leg.update(&order),
+                        Err(e) => {
+                            fetched = Err(e);
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if let Err(e) = fetched {
+                self.groups.insert(group_id, group);
+                return Err(e);
+            }
+            updates.extend(self.advance(client, group).await);
+        }
+
+        Ok(updates)
+    }
+
+    /// Cancels every working order in the group and drops any not placed yet.
+    pub async fn cancel(&mut self, client: &dyn AsyncClearstreetClient, group_id: &str) -> Result<(), Error> {
+        let Some(mut group) = self.groups.remove(group_id) else {
+            let msg = format!("Order group {} not found", group_id);
+            return Err(Error::new(ErrorType::NotFound, &msg));
+        };
+
+        let mut result = Ok(());
+        for leg in group.legs_mut() {
+            if let Err(e) = leg.cancel(client).await {
+                result = Err(e);
+            }
+        }
+
+        let previous = group.status.clone();
+        if result.is_ok() && !previous.is_done() {
+            group.status = GroupStatus::Canceled;
+            let _ = self.updates.send(GroupUpdate {
+                group_id: group.group_id.clone(),
+                previous,
+                current: GroupStatus::Canceled,
+            });
+        }
+        self.insert(group, Vec::new());
+
+        result
+    }
+
+    /// Drives every group from `feed` until it closes, resyncing first and after any gap.
+    ///
+    /// Call again with a new feed after reconnecting.
+    pub async fn run(&mut self, client: &dyn AsyncClearstreetClient, feed: &ActivityBroadcaster) -> Result<(), Error> {
+        let mut subscription = feed.subscribe(ActivityFilter::all().payload_type(PayloadType::OrderUpdate));
+        self.resync(client).await?;
+
+        while let Some(message) = subscription.recv().await {
+            match message {
+                Ok(message) => {
+                    self.apply_message(client, &message).await;
+                }
+                Err(e) => {
+                    tracing::warn!("Order group feed interrupted, resyncing: {}", e);
+                    self.resync(client).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+use crate::client::AsyncClearstreetClient;
+use crate::emulation::{average_price, created_at, format_quantity, parse_quantity, ChildOrder};
+use crate::error::{Error, ErrorType};
+use crate::orders::create::CreateOrderParams;
+use crate::orders::validation::validate_order;
+use crate::orders::{Order, OrderState, OrderType};
+use crate::trades::{list_trades_since, Trade};
+use crate::websockets::broadcast::{ActivityBroadcaster, ActivityFilter};
+use crate::websockets::payloads::ActivityMessage;
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcebergOptions {
+    /// Quantity shown per child order before randomisation.
+    pub display_quantity: f64,
+    /// How far each child may stray from `display_quantity`, e.g. `0.2` for ±20%.
+    pub variance: f64,
+    /// Child quantities are rounded to a multiple of this.
+    pub lot_size: f64,
+}
+
+impl IcebergOptions {
+    pub fn new(display_quantity: f64) -> Self {
+        Self {
+            display_quantity,
+            variance: 0.0,
+            lot_size: 1.0,
+        }
+    }
+
+    pub fn variance(mut self, variance: f64) -> Self {
+        self.variance = variance;
+        self
+    }
+
+    pub fn lot_size(mut self, lot_size: f64) -> Self {
+        self.lot_size = lot_size;
+        self
+    }
+
+    fn validate(&self, total: f64) -> Result<(), Error> {
+        let msg = if self.lot_size <= 0.0 {
+            "lot_size must be positive"
+        } else if self.display_quantity < self.lot_size || self.display_quantity > total {
+            "display_quantity must be between lot_size and the order quantity"
+        } else if !(0.0..1.0).contains(&self.variance) {
+            "variance must be within [0, 1)"
+        } else {
+            return Ok(());
+        };
+        Err(Error::new(ErrorType::ValidationError, msg))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcebergStatus {
+    Working,
+    Completed,
+    /// Canceled by the caller, or a child closed before filling, e.g. at the end of the day.
+    Canceled,
+    /// A child was rejected or could not be placed.
+    Failed(String),
+}
+
+impl IcebergStatus {
+    pub fn is_done(&self) -> bool {
+        !matches!(self, IcebergStatus::Working)
+    }
+}
+
+/// Parent-level totals across every child order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcebergProgress {
+    pub filled_quantity: f64,
+    pub remaining_quantity: f64,
+    /// Volume-weighted fill price, `None` before the first fill.
+    pub average_price: Option<f64>,
+    pub children: usize,
+    pub status: IcebergStatus,
+}
+
+/// Works a large limit order by showing only a slice of it at a time.
+///
+/// The parent quantity is posted as a sequence of child limit orders, each sized around
+/// `display_quantity`. When a child fills, as reported by `TradeNotice` or `OrderUpdate`,
+/// the next child is placed for what is left. Progress is reported across all children.
+pub struct Iceberg {
+    params: CreateOrderParams,
+    options: IcebergOptions,
+    total: f64,
+    children: Vec<ChildOrder>,
+    status: IcebergStatus,
+}
+
+impl Iceberg {
+    /// Validates `params`, a limit order for the full quantity, and places the first child.
+    pub async fn start(
+        client: &dyn AsyncClearstreetClient,
+        params: CreateOrderParams,
+        options: IcebergOptions,
+    ) -> Result<Self, Error> {
+        validate_order(&params)?;
+        if params.order_type != OrderType::Limit {
+            return Err(Error::new(ErrorType::ValidationError, "Iceberg orders must be limit orders"));
+        }
+        let total = parse_quantity(&params.quantity);
+        options.validate(total)?;
+
+        let mut iceberg = Self {
+            params,
+            options,
+            total,
+            children: Vec::new(),
+            status: IcebergStatus::Working,
+        };
+        iceberg.place_next(client).await?;
+
+        Ok(iceberg)
+    }
+
+    pub fn status(&self) -> &IcebergStatus {
+        &self.status
+    }
+
+    pub fn child_order_ids(&self) -> Vec<&str> {
+        self.children.iter().map(|child| child.order_id.as_str()).collect()
+    }
+
+    pub fn filled_quantity(&self) -> f64 {
+        self.children.iter().map(|child| child.filled().0).sum()
+    }
+
+    pub fn average_price(&self) -> Option<f64> {
+        average_price(&self.children)
+    }
+
+    pub fn progress(&self) -> IcebergProgress {
+        let filled_quantity = self.filled_quantity();
+        IcebergProgress {
+            filled_quantity,
+            remaining_quantity: (self.total - filled_quantity).max(0.0),
+            average_price: self.average_price(),
+            children: self.children.len(),
+            status: self.status.clone(),
+        }
+    }
+
+    fn next_quantity(&self, remaining: f64) -> f64 {
+        let IcebergOptions {
+            display_quantity,
+            variance,
+            lot_size,
+        } = self.options;
+
+        let jitter = if variance > 0.0 {
+            rand::rng().random_range(-variance..=variance)
+        } else {
+            0.0
+        };
+        let quantity = (display_quantity * (1.0 + jitter) / lot_size).round() * lot_size;
+        quantity.max(lot_size).min(remaining)
+    }
+
+    async fn place_next(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        let remaining = self.total - self.filled_quantity();
+        if remaining <= 0.0 {
+            self.status = IcebergStatus::Completed;
+            return Ok(());
+        }
+
+        let quantity = self.next_quantity(remaining);
+        let mut params = self.params.clone();
+        params.quantity = format_quantity(quantity);
+        params.reference_id = format!("{}-{}", self.params.reference_id, self.children.len() + 1);
+
+        match client.create_order(params).await {
+            Ok(response) => {
+                self.children.push(ChildOrder::new(response.order_id, quantity));
+                Ok(())
+            }
+            Err(e) => {
+                self.status = IcebergStatus::Failed(format!("Could not place child order: {}", e));
+                Err(e)
+            }
+        }
+    }
+
+    /// Replenishes once the working child has filled, or stops if it closed early.
+    async fn advance(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        if self.status.is_done() {
+            return Ok(());
+        }
+        let Some(child) = self.children.last() else {
+            return Ok(());
+        };
+
+        if child.is_filled() {
+            return self.place_next(client).await;
+        }
+        if let Some(order) = child.order.as_ref().filter(|order| order.is_terminal()) {
+            self.status = if order.state == OrderState::Rejected {
+                IcebergStatus::Failed(format!("Child order {} was rejected: {}", order.order_id, order.text))
+            } else {
+                IcebergStatus::Canceled
+            };
+        }
+        Ok(())
+    }
+
+    pub async fn apply_order(&mut self, client: &dyn AsyncClearstreetClient, order: &Order) -> Result<(), Error> {
+        let Some(child) = self.children.iter_mut().find(|child| child.order_id == order.order_id) else {
+            return Ok(());
+        };
+        child.apply_order(order);
+        self.advance(client).await
+    }
+
+    pub async fn apply_trade(&mut self, client: &dyn AsyncClearstreetClient, trade: &Trade) -> Result<(), Error> {
+        let Some(child) = self.children.iter_mut().find(|child| child.order_id == trade.order_id) else {
+            return Ok(());
+        };
+        child.apply_trade(trade);
+        self.advance(client).await
+    }
+
+    pub async fn apply_message(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        message: &ActivityMessage,
+    ) -> Result<(), Error> {
+        match message {
+            ActivityMessage::OrderUpdate(update) => self.apply_order(client, &update.payload.data).await,
+            ActivityMessage::TradeNotice(notice) => self.apply_trade(client, &notice.payload.data).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Re-fetches the working child and every fill, e.g. after a reconnect.
+    pub async fn resync(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        let Some(first) = self.children.first() else {
+            return Ok(());
+        };
+        let since = created_at(client, first).await?;
+        for trade in &list_trades_since(client, since).await? {
+            if let Some(child) = self.children.iter_mut().find(|child| child.order_id == trade.order_id) {
+                child.apply_trade(trade);
+            }
+        }
+
+        if let Some(order_id) = self.children.last().map(|child| child.order_id.clone()) {
+            let order = client.get_order(&order_id).await?;
+            return self.apply_order(client, &order).await;
+        }
+        Ok(())
+    }
+
+    /// Stops replenishing and cancels the working child. The status is left unchanged if the cancel fails.
+    pub async fn cancel(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        if self.status.is_done() {
+            return Ok(());
+        }
+
+        if let Some(child) = self.children.last().filter(|child| !child.is_closed() && !child.is_filled()) {
+            client.delete_order(&child.order_id).await?;
+        }
+        self.status = IcebergStatus::Canceled;
+        Ok(())
+    }
+
+    /// Works the order from `feed` until it is done or the feed closes.
+    pub async fn run(&mut self, client: &dyn AsyncClearstreetClient, feed: &ActivityBroadcaster) -> Result<(), Error> {
+        let mut subscription = feed.subscribe(ActivityFilter::all().symbol(&self.params.symbol));
+        self.resync(client).await?;
+
+        while !self.status.is_done() {
+            match subscription.recv().await {
+                Some(Ok(message)) => self.apply_message(client, &message).await?,
+                Some(Err(e)) => {
+                    tracing::warn!("Iceberg feed interrupted, resyncing: {}", e);
+                    self.resync(client).await?;
+                }
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+}
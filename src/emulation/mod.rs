@@ -0,0 +1,112 @@
+//! Order types Clear Street does not offer natively, emulated client-side on top of
+//! `create_order`, `update_order`, `delete_order` and the activity feed.
+//!
+//! Emulated orders only behave correctly while the process driving them is running and
+//! receiving updates; resting child orders are ordinary orders at the broker.
+
+use crate::client::AsyncClearstreetClient;
+use crate::error::Error;
+use crate::orders::Order;
+use crate::trades::Trade;
+use std::collections::HashMap;
+
+pub mod group;
+pub mod iceberg;
+pub mod slicer;
+pub mod trailing_stop;
+
+pub(crate) fn parse_quantity(value: &str) -> f64 {
+    value.trim().parse().unwrap_or_default()
+}
+
+/// Formats a quantity for the API without a trailing `.0` on whole numbers.
+pub(crate) fn format_quantity(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+/// A child order placed on behalf of an emulated parent, with the fills seen for it.
+#[derive(Debug, Clone)]
+pub(crate) struct ChildOrder {
+    pub order_id: String,
+    pub quantity: f64,
+    pub order: Option<Order>,
+    fills: HashMap<String, (f64, f64)>,
+}
+
+impl ChildOrder {
+    pub fn new(order_id: String, quantity: f64) -> Self {
+        Self {
+            order_id,
+            quantity,
+            order: None,
+            fills: HashMap::new(),
+        }
+    }
+
+    /// Keeps `order` if it is newer than the one held.
+    pub fn apply_order(&mut self, order: &Order) {
+        if self.order.as_ref().is_none_or(|held| order.version > held.version) {
+            self.order = Some(order.clone());
+        }
+    }
+
+    /// Records a fill once, however often the trade is seen.
+    pub fn apply_trade(&mut self, trade: &Trade) {
+        let fill = (parse_quantity(&trade.quantity), parse_quantity(&trade.price));
+        self.fills.insert(trade.trade_id.clone(), fill);
+    }
+
+    /// Filled quantity and notional, from trades unless the order reports more.
+    pub fn filled(&self) -> (f64, f64) {
+        let (quantity, notional) = self
+            .fills
+            .values()
+            .fold((0.0, 0.0), |(quantity, notional), (q, p)| (quantity + q, notional + q * p));
+
+        match &self.order {
+            Some(order) if parse_quantity(&order.filled_quantity) > quantity => {
+                let filled = parse_quantity(&order.filled_quantity);
+                (filled, filled * order.average_price)
+            }
+            _ => (quantity, notional),
+        }
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.filled().0 >= self.quantity
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.order.as_ref().is_some_and(Order::is_terminal)
+    }
+
+    /// Quantity that may still fill.
+    pub fn open_quantity(&self) -> f64 {
+        if self.is_closed() {
+            0.0
+        } else {
+            (self.quantity - self.filled().0).max(0.0)
+        }
+    }
+}
+
+/// When `child` was created, by the server's clock. Fetches the order if no update has been seen.
+pub(crate) async fn created_at(client: &dyn AsyncClearstreetClient, child: &ChildOrder) -> Result<i64, Error> {
+    match &child.order {
+        Some(order) => Ok(order.created_at),
+        None => Ok(client.get_order(&child.order_id).await?.created_at),
+    }
+}
+
+/// Volume-weighted price across `children`, `None` before the first fill.
+pub(crate) fn average_price<'a>(children: impl IntoIterator<Item = &'a ChildOrder>) -> Option<f64> {
+    let (quantity, notional) = children
+        .into_iter()
+        .map(ChildOrder::filled)
+        .fold((0.0, 0.0), |(q, n), (quantity, notional)| (q + quantity, n + notional));
+    (quantity > 0.0).then(|| notional / quantity)
+}
@@ -0,0 +1,534 @@
+use crate::client::AsyncClearstreetClient;
+use crate::emulation::{average_price, created_at, format_quantity, parse_quantity, ChildOrder};
+use crate::error::{Error, ErrorType};
+use crate::orders::create::CreateOrderParams;
+use crate::orders::strategy::Strategy;
+use crate::orders::validation::validate_order;
+use crate::orders::{Order, OrderState};
+use crate::trades::{list_trades_since, Trade};
+use crate::websockets::broadcast::{ActivityBroadcaster, ActivityFilter};
+use crate::websockets::payloads::ActivityMessage;
+use chrono::Utc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorType::ValidationError, msg)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SliceSchedule {
+    /// Equal quantity in each of `slices` intervals.
+    Twap { slices: usize },
+    /// Quantity in proportion to each weight, one interval per weight, e.g. a historical volume curve.
+    VolumeCurve(Vec<f64>),
+}
+
+impl SliceSchedule {
+    fn weights(&self) -> Vec<f64> {
+        match self {
+            SliceSchedule::Twap { slices } => vec![1.0; *slices],
+            SliceSchedule::VolumeCurve(weights) => weights.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlicerOptions {
+    /// Window start in milliseconds.
+    pub start_at: i64,
+    /// Window end in milliseconds. Anything still working is canceled then.
+    pub end_at: i64,
+    pub schedule: SliceSchedule,
+    /// Send the shortfall from earlier intervals with the next child. Otherwise each child is
+    /// capped at its own interval's quantity.
+    pub catch_up: bool,
+    /// Child quantities are rounded down to a multiple of this.
+    pub lot_size: f64,
+}
+
+impl SlicerOptions {
+    pub fn new(start_at: i64, end_at: i64, schedule: SliceSchedule) -> Self {
+        Self {
+            start_at,
+            end_at,
+            schedule,
+            catch_up: true,
+            lot_size: 1.0,
+        }
+    }
+
+    pub fn catch_up(mut self, catch_up: bool) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    pub fn lot_size(mut self, lot_size: f64) -> Self {
+        self.lot_size = lot_size;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlicerStatus {
+    Running,
+    /// No children are working; the schedule resumes, with catch-up, on [`Slicer::resume`].
+    Paused,
+    Completed,
+    /// The window ended before the full quantity filled.
+    Expired,
+    Canceled,
+    /// A child was rejected or could not be placed. Other children are canceled.
+    Failed(String),
+}
+
+impl SlicerStatus {
+    pub fn is_done(&self) -> bool {
+        !matches!(self, SlicerStatus::Running | SlicerStatus::Paused)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlicerProgress {
+    pub filled_quantity: f64,
+    /// Quantity the schedule expects filled by now.
+    pub target_quantity: f64,
+    pub total_quantity: f64,
+    /// Volume-weighted fill price, `None` before the first fill.
+    pub average_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlicerEvent {
+    ChildPlaced { order_id: String, slice: usize, quantity: f64 },
+    ChildCanceled { order_id: String },
+    Filled(SlicerProgress),
+    StatusChanged { previous: SlicerStatus, current: SlicerStatus },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlicerCommand {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+/// Sends commands to a [`Slicer`] while it is in [`Slicer::run`].
+#[derive(Debug, Clone)]
+pub struct SlicerHandle {
+    commands: mpsc::UnboundedSender<SlicerCommand>,
+}
+
+impl SlicerHandle {
+    pub fn pause(&self) {
+        let _ = self.commands.send(SlicerCommand::Pause);
+    }
+
+    pub fn resume(&self) {
+        let _ = self.commands.send(SlicerCommand::Resume);
+    }
+
+    pub fn cancel(&self) {
+        let _ = self.commands.send(SlicerCommand::Cancel);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SliceChild {
+    slice: usize,
+    cancel_requested: bool,
+    order: ChildOrder,
+}
+
+impl SliceChild {
+    fn is_working(&self) -> bool {
+        !self.order.is_closed() && !self.order.is_filled()
+    }
+}
+
+/// Works a direct market access order over a time window as a series of child orders.
+///
+/// The window is split into intervals, each due a share of the quantity: equal shares for
+/// TWAP, or shares proportional to a caller-supplied volume curve. On each
+/// [`Slicer::tick`] children left over from earlier intervals are canceled and a new child is
+/// sent for whatever the schedule expects filled by now but is neither filled nor working.
+/// Fills come from `OrderUpdate` and `TradeNotice` messages. Progress and status changes are
+/// published to [`Slicer::subscribe`] receivers.
+pub struct Slicer {
+    params: CreateOrderParams,
+    options: SlicerOptions,
+    total: f64,
+    /// Cumulative quantity due by the end of each interval.
+    targets: Vec<f64>,
+    children: Vec<SliceChild>,
+    status: SlicerStatus,
+    events: broadcast::Sender<SlicerEvent>,
+    commands: mpsc::UnboundedSender<SlicerCommand>,
+    command_receiver: mpsc::UnboundedReceiver<SlicerCommand>,
+}
+
+impl Slicer {
+    /// Validates `params`, the parent order for the full quantity. Nothing is sent until the window opens.
+    pub fn new(params: CreateOrderParams, options: SlicerOptions) -> Result<Self, Error> {
+        validate_order(&params)?;
+        if !matches!(params.strategy, Strategy::DirectMarketAccess { .. }) {
+            return Err(invalid("The slicer only works DirectMarketAccess orders"));
+        }
+        if options.end_at <= options.start_at {
+            return Err(invalid("Slicer start_at must be before end_at"));
+        }
+        if options.lot_size <= 0.0 {
+            return Err(invalid("lot_size must be positive"));
+        }
+
+        let weights = options.schedule.weights();
+        let weight_total: f64 = weights.iter().sum();
+        if weights.is_empty() || weights.iter().any(|w| *w < 0.0 || !w.is_finite()) || weight_total <= 0.0 {
+            return Err(invalid("The schedule needs at least one interval and non-negative weights"));
+        }
+
+        let total = parse_quantity(&params.quantity);
+        let mut cumulative = 0.0;
+        let mut targets: Vec<f64> = weights
+            .iter()
+            .map(|weight| {
+                cumulative += weight;
+                (total * cumulative / weight_total / options.lot_size).floor() * options.lot_size
+            })
+            .collect();
+        if let Some(last) = targets.last_mut() {
+            *last = total;
+        }
+
+        let (events, _) = broadcast::channel(1024);
+        let (commands, command_receiver) = mpsc::unbounded_channel();
+
+        Ok(Self {
+            params,
+            options,
+            total,
+            targets,
+            children: Vec::new(),
+            status: SlicerStatus::Running,
+            events,
+            commands,
+            command_receiver,
+        })
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SlicerEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn handle(&self) -> SlicerHandle {
+        SlicerHandle {
+            commands: self.commands.clone(),
+        }
+    }
+
+    pub fn status(&self) -> &SlicerStatus {
+        &self.status
+    }
+
+    pub fn child_order_ids(&self) -> Vec<&str> {
+        self.children.iter().map(|child| child.order.order_id.as_str()).collect()
+    }
+
+    pub fn filled_quantity(&self) -> f64 {
+        self.children.iter().map(|child| child.order.filled().0).sum()
+    }
+
+    pub fn average_price(&self) -> Option<f64> {
+        average_price(self.children.iter().map(|child| &child.order))
+    }
+
+    /// The interval `now` falls in, `None` before the window opens.
+    pub fn slice_at(&self, now: i64) -> Option<usize> {
+        if now < self.options.start_at {
+            return None;
+        }
+        let slices = self.targets.len() as i64;
+        let elapsed = (now - self.options.start_at) as i128 * slices as i128;
+        let window = (self.options.end_at - self.options.start_at) as i128;
+        Some((elapsed / window).min(slices as i128 - 1) as usize)
+    }
+
+    /// Quantity the schedule expects filled by `now`.
+    pub fn target_at(&self, now: i64) -> f64 {
+        self.slice_at(now).map(|slice| self.targets[slice]).unwrap_or_default()
+    }
+
+    pub fn progress(&self, now: i64) -> SlicerProgress {
+        SlicerProgress {
+            filled_quantity: self.filled_quantity(),
+            target_quantity: self.target_at(now),
+            total_quantity: self.total,
+            average_price: self.average_price(),
+        }
+    }
+
+    fn set_status(&mut self, status: SlicerStatus) {
+        if self.status != status {
+            let previous = std::mem::replace(&mut self.status, status.clone());
+            let _ = self.events.send(SlicerEvent::StatusChanged { previous, current: status });
+        }
+    }
+
+    /// Cancels every selected working child, returning the failures together.
+    async fn cancel_children<F>(&mut self, client: &dyn AsyncClearstreetClient, select: F) -> Result<(), Error>
+    where
+        F: Fn(&SliceChild) -> bool,
+    {
+        let mut error_type = None;
+        let mut failures = Vec::new();
+
+        for child in self.children.iter_mut() {
+            if child.cancel_requested || !child.is_working() || !select(child) {
+                continue;
+            }
+            if let Err(e) = client.delete_order(&child.order.order_id).await {
+                failures.push(format!("{}: {}", child.order.order_id, e.message));
+                error_type.get_or_insert(e.error_type);
+                continue;
+            }
+            child.cancel_requested = true;
+            let _ = self.events.send(SlicerEvent::ChildCanceled {
+                order_id: child.order.order_id.clone(),
+            });
+        }
+
+        match error_type {
+            None => Ok(()),
+            Some(error_type) => {
+                let msg = format!("Failed to cancel {} child orders: {}", failures.len(), failures.join("; "));
+                Err(Error::new(error_type, &msg))
+            }
+        }
+    }
+
+    fn open_quantity(&self) -> f64 {
+        self.children.iter().map(|child| child.order.open_quantity()).sum()
+    }
+
+    /// Cancels stale children and sends what the schedule is owed at `now` (milliseconds).
+    pub async fn tick(&mut self, client: &dyn AsyncClearstreetClient, now: i64) -> Result<(), Error> {
+        if self.status != SlicerStatus::Running {
+            return Ok(());
+        }
+        if self.filled_quantity() >= self.total {
+            self.set_status(SlicerStatus::Completed);
+            return Ok(());
+        }
+
+        if now >= self.options.end_at {
+            self.cancel_children(client, |_| true).await?;
+            if self.children.iter().all(|child| child.order.is_closed() || child.order.is_filled()) {
+                self.set_status(SlicerStatus::Expired);
+            }
+            return Ok(());
+        }
+
+        let Some(slice) = self.slice_at(now) else {
+            return Ok(());
+        };
+
+        // Children from earlier intervals make way for this one.
+        self.cancel_children(client, |child| child.slice < slice).await?;
+
+        // Quantity on a child being canceled may still fill, so it is not resent until closed.
+        let outstanding = self.total - self.filled_quantity() - self.open_quantity();
+        let mut due = self.targets[slice] - self.filled_quantity() - self.open_quantity();
+        if !self.options.catch_up {
+            let scheduled = self.targets[slice] - slice.checked_sub(1).map(|s| self.targets[s]).unwrap_or_default();
+            let placed: f64 = self.children.iter().filter(|c| c.slice == slice).map(|c| c.order.quantity).sum();
+            due = due.min(scheduled - placed);
+        }
+
+        let lot_size = self.options.lot_size;
+        let quantity = if due >= outstanding {
+            outstanding
+        } else {
+            (due / lot_size).floor() * lot_size
+        };
+        if quantity <= 0.0 || (quantity < lot_size && quantity < outstanding) {
+            return Ok(());
+        }
+
+        self.place(client, slice, quantity).await
+    }
+
+    async fn place(&mut self, client: &dyn AsyncClearstreetClient, slice: usize, quantity: f64) -> Result<(), Error> {
+        let mut params = self.params.clone();
+        params.quantity = format_quantity(quantity);
+        params.reference_id = format!("{}-{}", self.params.reference_id, self.children.len() + 1);
+
+        match client.create_order(params).await {
+            Ok(response) => {
+                let _ = self.events.send(SlicerEvent::ChildPlaced {
+                    order_id: response.order_id.clone(),
+                    slice,
+                    quantity,
+                });
+                self.children.push(SliceChild {
+                    slice,
+                    cancel_requested: false,
+                    order: ChildOrder::new(response.order_id, quantity),
+                });
+                Ok(())
+            }
+            Err(e) => {
+                self.fail(client, format!("Could not place child order: {}", e)).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn fail(&mut self, client: &dyn AsyncClearstreetClient, reason: String) {
+        tracing::warn!("Slicer for {} failed: {}", self.params.symbol, reason);
+        if let Err(e) = self.cancel_children(client, |_| true).await {
+            tracing::warn!("Could not cancel slicer children: {}", e);
+        }
+        self.set_status(SlicerStatus::Failed(reason));
+    }
+
+    /// Reports fills and stops on a rejected child.
+    async fn after_update(&mut self, client: &dyn AsyncClearstreetClient, filled_before: f64) {
+        let filled = self.filled_quantity();
+        if filled != filled_before {
+            let _ = self.events.send(SlicerEvent::Filled(self.progress(Utc::now().timestamp_millis())));
+        }
+        if self.status.is_done() {
+            return;
+        }
+
+        let rejected = self
+            .children
+            .iter()
+            .filter_map(|child| child.order.order.as_ref())
+            .find(|order| order.state == OrderState::Rejected)
+            .map(|order| format!("Child order {} was rejected: {}", order.order_id, order.text));
+        if let Some(reason) = rejected {
+            self.fail(client, reason).await;
+        } else if filled >= self.total {
+            self.set_status(SlicerStatus::Completed);
+        }
+    }
+
+    pub async fn apply_order(&mut self, client: &dyn AsyncClearstreetClient, order: &Order) {
+        let filled_before = self.filled_quantity();
+        let Some(child) = self.children.iter_mut().find(|child| child.order.order_id == order.order_id) else {
+            return;
+        };
+        child.order.apply_order(order);
+        self.after_update(client, filled_before).await;
+    }
+
+    pub async fn apply_trade(&mut self, client: &dyn AsyncClearstreetClient, trade: &Trade) {
+        let filled_before = self.filled_quantity();
+        let Some(child) = self.children.iter_mut().find(|child| child.order.order_id == trade.order_id) else {
+            return;
+        };
+        child.order.apply_trade(trade);
+        self.after_update(client, filled_before).await;
+    }
+
+    pub async fn apply_message(&mut self, client: &dyn AsyncClearstreetClient, message: &ActivityMessage) {
+        match message {
+            ActivityMessage::OrderUpdate(update) => self.apply_order(client, &update.payload.data).await,
+            ActivityMessage::TradeNotice(notice) => self.apply_trade(client, &notice.payload.data).await,
+            _ => {}
+        }
+    }
+
+    /// Re-fetches fills and working children, e.g. after a reconnect.
+    pub async fn resync(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        let Some(first) = self.children.first() else {
+            return Ok(());
+        };
+        let since = created_at(client, &first.order).await?;
+        for trade in &list_trades_since(client, since).await? {
+            self.apply_trade(client, trade).await;
+        }
+
+        let working: Vec<String> = self
+            .children
+            .iter()
+            .filter(|child| !child.order.is_closed())
+            .map(|child| child.order.order_id.clone())
+            .collect();
+        for order_id in working {
+            let order = client.get_order(&order_id).await?;
+            self.apply_order(client, &order).await;
+        }
+        Ok(())
+    }
+
+    /// Cancels working children and stops sending new ones until resumed.
+    pub async fn pause(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        if self.status != SlicerStatus::Running {
+            return Ok(());
+        }
+        self.cancel_children(client, |_| true).await?;
+        self.set_status(SlicerStatus::Paused);
+        Ok(())
+    }
+
+    pub fn resume(&mut self) {
+        if self.status == SlicerStatus::Paused {
+            self.set_status(SlicerStatus::Running);
+        }
+    }
+
+    pub async fn cancel(&mut self, client: &dyn AsyncClearstreetClient) -> Result<(), Error> {
+        if self.status.is_done() {
+            return Ok(());
+        }
+        self.cancel_children(client, |_| true).await?;
+        self.set_status(SlicerStatus::Canceled);
+        Ok(())
+    }
+
+    async fn command(&mut self, client: &dyn AsyncClearstreetClient, command: SlicerCommand) -> Result<(), Error> {
+        match command {
+            SlicerCommand::Pause => self.pause(client).await,
+            SlicerCommand::Resume => {
+                self.resume();
+                Ok(())
+            }
+            SlicerCommand::Cancel => self.cancel(client).await,
+        }
+    }
+
+    /// Works the schedule until it is done, ticking every `tick_interval` and following `feed`.
+    ///
+    /// Commands sent through [`Slicer::handle`] are applied as they arrive. Returns once the
+    /// slicer is done or the feed closes; call again with a new feed after reconnecting.
+    pub async fn run(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        feed: &ActivityBroadcaster,
+        tick_interval: Duration,
+    ) -> Result<(), Error> {
+        let mut subscription = feed.subscribe(ActivityFilter::all().symbol(&self.params.symbol));
+        let mut interval = tokio::time::interval(tick_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        self.resync(client).await?;
+
+        while !self.status.is_done() {
+            tokio::select! {
+                _ = interval.tick() => self.tick(client, Utc::now().timestamp_millis()).await?,
+                Some(command) = self.command_receiver.recv() => self.command(client, command).await?,
+                message = subscription.recv() => match message {
+                    Some(Ok(message)) => self.apply_message(client, &message).await,
+                    Some(Err(e)) => {
+                        tracing::warn!("Slicer feed interrupted, resyncing: {}", e);
+                        self.resync(client).await?;
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
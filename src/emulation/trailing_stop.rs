@@ -0,0 +1,277 @@
+use crate::client::AsyncClearstreetClient;
+use crate::emulation::parse_quantity;
+use crate::error::{Error, ErrorType};
+use crate::orders::create::CreateOrderParams;
+use crate::orders::update::UpdateOrderRequestBody;
+use crate::orders::validation::validate_order;
+use crate::orders::{Order, OrderSide, OrderType};
+use crate::websockets::broadcast::{ActivityBroadcaster, ActivityFilter};
+use crate::websockets::payloads::ActivityMessage;
+use futures_util::{Stream, StreamExt};
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailOffset {
+    /// Distance from the best price, in price units.
+    Absolute(f64),
+    /// Distance from the best price, as a percentage of it.
+    Percent(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailingStopOptions {
+    pub offset: TrailOffset,
+    /// Smallest stop move worth an amendment.
+    pub min_step: f64,
+    /// Minimum time between amendments.
+    pub min_interval: Duration,
+    /// Price increment stops are rounded to, away from the market.
+    pub tick_size: f64,
+}
+
+impl TrailingStopOptions {
+    pub fn new(offset: TrailOffset) -> Self {
+        Self {
+            offset,
+            min_step: 0.01,
+            min_interval: Duration::from_secs(1),
+            tick_size: 0.01,
+        }
+    }
+
+    pub fn min_step(mut self, min_step: f64) -> Self {
+        self.min_step = min_step;
+        self
+    }
+
+    pub fn min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let offset_ok = match self.offset {
+            TrailOffset::Absolute(offset) => offset > 0.0,
+            TrailOffset::Percent(percent) => percent > 0.0 && percent < 100.0,
+        };
+        if !offset_ok {
+            let msg = format!("Invalid trailing offset {:?}", self.offset);
+            return Err(Error::new(ErrorType::ValidationError, &msg));
+        }
+        if self.tick_size <= 0.0 || self.min_step < 0.0 {
+            return Err(Error::new(ErrorType::ValidationError, "tick_size must be positive and min_step not negative"));
+        }
+        Ok(())
+    }
+}
+
+/// A stop order that follows the price, kept at `offset` behind the best price seen.
+///
+/// A sell stop protects a long position and only ever moves up; a buy stop protects a
+/// short and only ever moves down. Each price from [`TrailingStop::on_price`] may amend the
+/// resting stop via `update_order`, at most once per `min_interval` and only when it moves
+/// by at least `min_step`. A move held back by the rate limit is applied by a later price,
+/// since the target is always derived from the best price seen.
+///
+/// Once the order reports a fill the stop has triggered, so it is no longer amended.
+pub struct TrailingStop {
+    order_id: String,
+    side: OrderSide,
+    options: TrailingStopOptions,
+    quantity: String,
+    stop_price: f64,
+    best_price: f64,
+    last_amended: Option<Instant>,
+    order: Option<Order>,
+}
+
+impl TrailingStop {
+    /// Places `params` as a stop order trailing `reference_price`.
+    pub async fn place(
+        client: &dyn AsyncClearstreetClient,
+        mut params: CreateOrderParams,
+        reference_price: f64,
+        options: TrailingStopOptions,
+    ) -> Result<Self, Error> {
+        options.validate()?;
+
+        let side = params.order_side.clone();
+        let stop_price = stop_for(&side, &options, reference_price);
+
+        params.order_type = OrderType::Stop;
+        params.price = None;
+        params.stop_price = Some(format_price(stop_price, options.tick_size));
+        validate_order(&params)?;
+        let quantity = params.quantity.clone();
+
+        let response = client.create_order(params).await?;
+
+        Ok(Self {
+            order_id: response.order_id,
+            side,
+            options,
+            quantity,
+            stop_price,
+            best_price: reference_price,
+            last_amended: None,
+            order: None,
+        })
+    }
+
+    pub fn order_id(&self) -> &str {
+        &self.order_id
+    }
+
+    pub fn stop_price(&self) -> f64 {
+        self.stop_price
+    }
+
+    /// Highest price seen for a sell stop, lowest for a buy stop.
+    pub fn best_price(&self) -> f64 {
+        self.best_price
+    }
+
+    /// Latest state reported for the order.
+    pub fn order(&self) -> Option<&Order> {
+        self.order.as_ref()
+    }
+
+    pub fn filled_quantity(&self) -> f64 {
+        self.order.as_ref().map(|order| parse_quantity(&order.filled_quantity)).unwrap_or_default()
+    }
+
+    /// Whether the stop has triggered or the order is done, so it no longer trails.
+    pub fn is_done(&self) -> bool {
+        self.filled_quantity() > 0.0 || self.order.as_ref().is_some_and(Order::is_terminal)
+    }
+
+    fn improves(&self, stop_price: f64) -> bool {
+        let step = self.options.min_step.max(f64::EPSILON);
+        match self.side {
+            OrderSide::Buy => self.stop_price - stop_price >= step,
+            _ => stop_price - self.stop_price >= step,
+        }
+    }
+
+    /// Tracks `price` and amends the stop if it should move. Returns the new stop price.
+    pub async fn on_price(&mut self, client: &dyn AsyncClearstreetClient, price: f64) -> Result<Option<f64>, Error> {
+        if self.is_done() || !price.is_finite() || price <= 0.0 {
+            return Ok(None);
+        }
+
+        self.best_price = match self.side {
+            OrderSide::Buy => self.best_price.min(price),
+            _ => self.best_price.max(price),
+        };
+
+        let stop_price = stop_for(&self.side, &self.options, self.best_price);
+        if !self.improves(stop_price) {
+            return Ok(None);
+        }
+        // An amendment in flight has to be acknowledged before the next one.
+        if self.order.as_ref().is_some_and(|order| !order.can_replace()) {
+            return Ok(None);
+        }
+        if self.last_amended.is_some_and(|at| at.elapsed() < self.options.min_interval) {
+            return Ok(None);
+        }
+
+        let params = UpdateOrderRequestBody {
+            quantity: self.quantity.clone(),
+            price: None,
+            stop_price: Some(format_price(stop_price, self.options.tick_size)),
+        };
+        client.update_order(&self.order_id, params).await?;
+
+        self.stop_price = stop_price;
+        self.last_amended = Some(Instant::now());
+        Ok(Some(stop_price))
+    }
+
+    /// Records the latest state of the stop order, ignoring other orders and stale versions.
+    ///
+    /// The local stop price follows the order's, so a stop changed elsewhere is trailed from there.
+    pub fn apply_order(&mut self, order: &Order) {
+        if order.order_id != self.order_id || self.order.as_ref().is_some_and(|held| order.version <= held.version) {
+            return;
+        }
+
+        // Amend from the quantity and stop price the broker holds, not the ones last sent.
+        self.quantity = order.quantity.clone();
+        if let Some(stop_price) = order.stop_price.as_deref().and_then(|price| price.trim().parse().ok()) {
+            self.stop_price = stop_price;
+        }
+        self.order = Some(order.clone());
+    }
+
+    pub fn apply_message(&mut self, message: &ActivityMessage) {
+        if let ActivityMessage::OrderUpdate(update) = message {
+            self.apply_order(&update.payload.data);
+        }
+    }
+
+    /// Trails `prices` until the stop triggers, the order closes or either input ends.
+    pub async fn run<S>(
+        &mut self,
+        client: &dyn AsyncClearstreetClient,
+        prices: S,
+        feed: &ActivityBroadcaster,
+    ) -> Result<(), Error>
+    where
+        S: Stream<Item = f64>,
+    {
+        let mut subscription = feed.subscribe(ActivityFilter::all().order_id(&self.order_id));
+        let mut prices = std::pin::pin!(prices);
+
+        // Pick up anything that happened before subscribing.
+        let order = client.get_order(&self.order_id).await?;
+        self.apply_order(&order);
+
+        while !self.is_done() {
+            tokio::select! {
+                price = prices.next() => match price {
+                    Some(price) => {
+                        self.on_price(client, price).await?;
+                    }
+                    None => break,
+                },
+                message = subscription.recv() => match message {
+                    Some(Ok(message)) => self.apply_message(&message),
+                    Some(Err(e)) => {
+                        tracing::warn!("Trailing stop feed interrupted, refreshing order: {}", e);
+                        let order = client.get_order(&self.order_id).await?;
+                        self.apply_order(&order);
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn stop_for(side: &OrderSide, options: &TrailingStopOptions, best_price: f64) -> f64 {
+    let offset = match options.offset {
+        TrailOffset::Absolute(offset) => offset,
+        TrailOffset::Percent(percent) => best_price * percent / 100.0,
+    };
+
+    // Round away from the market so the offset is never tighter than asked for.
+    let ticks = match side {
+        OrderSide::Buy => ((best_price + offset) / options.tick_size - 1e-9).ceil(),
+        _ => ((best_price - offset) / options.tick_size + 1e-9).floor(),
+    };
+    ticks * options.tick_size
+}
+
+fn format_price(price: f64, tick_size: f64) -> String {
+    let decimals = (-tick_size.log10()).ceil().max(0.0) as usize;
+    format!("{:.*}", decimals, price)
+}
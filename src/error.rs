@@ -2,7 +2,7 @@ use std::fmt::Display;
 use reqwest::header::InvalidHeaderValue;
 use serde::{Deserialize, Serialize};
 use tokio_tungstenite::tungstenite;
-use crate::orders::OrderState;
+use crate::websockets::payloads::{ErrorCategory, ErrorNotice};
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[allow(clippy::enum_variant_names)]
@@ -17,6 +17,13 @@ pub enum ErrorType {
     HttpError,
     SerializationError,
     NotFound,
+    ChannelError,
+    ValidationError,
+    OrderRejected,
+    RateLimited,
+    OrderClosed,
+    InvalidStateTransition,
+    VersionConflict,
 }
 
 
@@ -26,12 +33,6 @@ pub struct Error {
     pub message: String,
 }
 
-impl Error {
-    pub(crate) fn internal(p0: &str) -> Result<OrderState, Error> {
-        Err(Error::new(ErrorType::InternalError, p0))
-    }
-}
-
 impl Error {
     pub fn new(error_type: ErrorType, message: &str) -> Self {
         Error {
@@ -72,6 +73,32 @@ impl From<reqwest::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::new(ErrorType::IoError, &err.to_string())
+    }
+}
+
+impl From<&ErrorNotice> for Error {
+    fn from(notice: &ErrorNotice) -> Self {
+        let error_type = match notice.category() {
+            ErrorCategory::Authentication => ErrorType::AuthenticationError,
+            ErrorCategory::Validation => ErrorType::ValidationError,
+            ErrorCategory::Rejected => ErrorType::OrderRejected,
+            ErrorCategory::RateLimit => ErrorType::RateLimited,
+            ErrorCategory::Internal | ErrorCategory::Unknown => ErrorType::ThirdPartyError,
+        };
+
+        Error::new(error_type, notice.message())
+    }
+}
+
+impl From<ErrorNotice> for Error {
+    fn from(notice: ErrorNotice) -> Self {
+        Error::from(&notice)
+    }
+}
+
 impl From<tungstenite::error::Error> for Error {
     fn from(err: tungstenite::error::Error) -> Self {
         Error::new(ErrorType::IoError, &err.to_string())
@@ -1,21 +1,74 @@
 use crate::client::async_client::AsyncClient;
 use crate::error::Error;
+use crate::instruments::option_symbol::OptionSymbol;
 use crate::utils::parse_response;
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+pub mod option_symbol;
+
+/// Shares delivered per standard listed equity option contract.
+pub const STANDARD_CONTRACT_MULTIPLIER: f64 = 100.0;
+
+wire_enum! {
+    pub enum AssetClass {
+        Equity => "equity",
+        Option => "option",
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SymbolDetail {
     pub symbol: String,
     pub symbol_format: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Instrument {
     pub symbols: Vec<SymbolDetail>,
-    pub asset_class: String,
+    pub asset_class: AssetClass,
     pub primary_exchange: String,
     pub description: String,
+    /// Units of the underlying per contract, sent for options.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiplier: Option<f64>,
+    /// Underlying symbol, sent for options.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlying: Option<String>,
+}
+
+impl Instrument {
+    pub fn is_option(&self) -> bool {
+        matches!(self.asset_class, AssetClass::Option)
+    }
+
+    /// The multiplier sent by the API, or the standard one for options and 1 otherwise.
+    pub fn contract_multiplier(&self) -> f64 {
+        match self.multiplier {
+            Some(multiplier) => multiplier,
+            None if self.is_option() => STANDARD_CONTRACT_MULTIPLIER,
+            None => 1.0,
+        }
+    }
+
+    /// The option contract, parsed from the first OSI symbol listed.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        if !self.is_option() {
+            return None;
+        }
+
+        self.symbols.iter().find_map(|detail| match detail.symbol_format.as_str() {
+            "osi" => OptionSymbol::from_osi(&detail.symbol).ok(),
+            _ => None,
+        })
+    }
+
+    /// The underlying symbol, falling back to the root of the option symbol.
+    pub fn underlying(&self) -> Option<String> {
+        self.underlying
+            .clone()
+            .or_else(|| self.option_symbol().map(|option| option.root().to_string()))
+    }
 }
 
 #[cfg(feature = "async")]
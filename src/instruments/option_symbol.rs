@@ -0,0 +1,220 @@
+use crate::error::{Error, ErrorType};
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+const MAX_ROOT_LEN: usize = 6;
+// OSI strikes are 8 digits with three implied decimals.
+const MAX_STRIKE_THOUSANDTHS: u64 = 99_999_999;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+impl OptionType {
+    pub fn as_char(&self) -> char {
+        match self {
+            OptionType::Call => 'C',
+            OptionType::Put => 'P',
+        }
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'C' => Some(OptionType::Call),
+            'P' => Some(OptionType::Put),
+            _ => None,
+        }
+    }
+}
+
+fn parse_error(symbol: &str, reason: &str) -> Error {
+    let msg = format!("Invalid option symbol '{}': {}", symbol, reason);
+    Error::new(ErrorType::ParseError, &msg)
+}
+
+fn validation_error(reason: &str) -> Error {
+    Error::new(ErrorType::ValidationError, reason)
+}
+
+/// Parses a decimal strike such as `"150"` or `"52.125"` into thousandths.
+fn parse_strike(strike: &str) -> Result<u64, Error> {
+    let strike = strike.trim();
+    let (whole, fraction) = strike.split_once('.').unwrap_or((strike, ""));
+
+    let digits_only = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if whole.is_empty() || !digits_only(whole) || !digits_only(fraction) {
+        return Err(validation_error(&format!("Strike '{}' is not a decimal number", strike)));
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > 3 {
+        return Err(validation_error(&format!("Strike '{}' has more than three decimals", strike)));
+    }
+
+    let whole: u64 = whole
+        .parse()
+        .map_err(|_| validation_error(&format!("Strike '{}' is out of range", strike)))?;
+    let fraction: u64 = format!("{:0<3}", fraction).parse().unwrap_or_default();
+
+    Ok(whole.saturating_mul(1000).saturating_add(fraction))
+}
+
+/// A listed option contract, convertible to and from OSI symbols.
+///
+/// OSI is the 21 character OCC format, e.g. `AAPL  240119C00150000`: the root padded
+/// to six characters, expiry as `YYMMDD`, `C` or `P`, and the strike in thousandths
+/// padded to eight digits.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OptionSymbol {
+    root: String,
+    expiry: NaiveDate,
+    option_type: OptionType,
+    strike_thousandths: u64,
+}
+
+impl OptionSymbol {
+    /// Builds a contract from a decimal strike such as `"150"` or `"52.5"`.
+    pub fn new(root: &str, expiry: NaiveDate, option_type: OptionType, strike: &str) -> Result<Self, Error> {
+        Self::from_strike_thousandths(root, expiry, option_type, parse_strike(strike)?)
+    }
+
+    pub fn from_strike_thousandths(
+        root: &str,
+        expiry: NaiveDate,
+        option_type: OptionType,
+        strike_thousandths: u64,
+    ) -> Result<Self, Error> {
+        let root = root.trim().to_uppercase();
+
+        if root.is_empty() || root.len() > MAX_ROOT_LEN {
+            return Err(validation_error(&format!("Option root '{}' must be 1 to 6 characters", root)));
+        }
+        if !root.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(validation_error(&format!("Option root '{}' must be alphanumeric", root)));
+        }
+        if strike_thousandths == 0 || strike_thousandths > MAX_STRIKE_THOUSANDTHS {
+            return Err(validation_error(&format!(
+                "Strike {} is outside the OSI range",
+                strike_thousandths as f64 / 1000.0
+            )));
+        }
+
+        Ok(Self {
+            root,
+            expiry,
+            option_type,
+            strike_thousandths,
+        })
+    }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub fn expiry(&self) -> NaiveDate {
+        self.expiry
+    }
+
+    pub fn option_type(&self) -> OptionType {
+        self.option_type
+    }
+
+    pub fn strike_thousandths(&self) -> u64 {
+        self.strike_thousandths
+    }
+
+    /// Strike as a decimal string without trailing zeros, e.g. `"52.5"`.
+    pub fn strike(&self) -> String {
+        let whole = self.strike_thousandths / 1000;
+        let fraction = self.strike_thousandths % 1000;
+
+        if fraction == 0 {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, format!("{:03}", fraction).trim_end_matches('0'))
+        }
+    }
+
+    pub fn to_osi(&self) -> String {
+        format!(
+            "{:<6}{}{}{:08}",
+            self.root,
+            self.expiry.format("%y%m%d"),
+            self.option_type.as_char(),
+            self.strike_thousandths
+        )
+    }
+
+    /// Parses an OSI symbol. The root padding may be omitted.
+    pub fn from_osi(symbol: &str) -> Result<Self, Error> {
+        let trimmed = symbol.trim();
+        if !trimmed.is_ascii() || trimmed.len() < 16 {
+            return Err(parse_error(symbol, "too short for an OSI symbol"));
+        }
+
+        let (root, rest) = trimmed.split_at(trimmed.len() - 15);
+        if root.len() < MAX_ROOT_LEN && root.ends_with(' ') {
+            return Err(parse_error(symbol, "root must be padded to six characters or not at all"));
+        }
+
+        let (date, rest) = rest.split_at(6);
+        let (option_type, strike) = rest.split_at(1);
+
+        let expiry = NaiveDate::parse_from_str(date, "%y%m%d").map_err(|_| parse_error(symbol, "invalid expiry"))?;
+        let option_type = option_type
+            .chars()
+            .next()
+            .and_then(OptionType::from_char)
+            .ok_or_else(|| parse_error(symbol, "expected C or P"))?;
+        if !strike.chars().all(|c| c.is_ascii_digit()) {
+            return Err(parse_error(symbol, "strike must be eight digits"));
+        }
+        let strike_thousandths = strike.parse().map_err(|_| parse_error(symbol, "invalid strike"))?;
+
+        Self::from_strike_thousandths(root, expiry, option_type, strike_thousandths)
+            .map_err(|e| parse_error(symbol, &e.message))
+    }
+}
+
+/// Parses `symbol` as an OSI option symbol.
+pub fn parse_option_symbol(symbol: &str) -> Option<OptionSymbol> {
+    OptionSymbol::from_osi(symbol).ok()
+}
+
+impl Display for OptionSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_osi())
+    }
+}
+
+impl FromStr for OptionSymbol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_osi(s)
+    }
+}
+
+impl Serialize for OptionSymbol {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_osi())
+    }
+}
+
+impl<'de> Deserialize<'de> for OptionSymbol {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let symbol = String::deserialize(deserializer)?;
+        Self::from_osi(&symbol).map_err(|e| serde::de::Error::custom(e.message))
+    }
+}
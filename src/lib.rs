@@ -1,3 +1,6 @@
+#[macro_use]
+mod macros;
+
 pub mod authentication;
 pub mod error;
 pub mod orders;
@@ -9,3 +12,6 @@ mod mock;
 pub mod client;
 pub mod trades;
 pub mod instruments;
+pub mod emulation;
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,84 @@
+/// Declares an enum that travels over the wire as a string.
+///
+/// Each variant is mapped to its wire value once, and `FromStr`, `Display`, `Serialize`
+/// and `Deserialize` are all derived from that mapping. `FromStr` only accepts listed
+/// values; `Deserialize` keeps anything else in an `Unknown(String)` variant so new
+/// server values still parse and round-trip.
+macro_rules! wire_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $wire:literal,
+            )+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )+
+            /// A value this SDK does not know yet, kept as sent by the API.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value used on the wire.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $( $name::$variant => $wire, )+
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, $name::Unknown(_))
+            }
+        }
+
+        /// Accepts only the listed wire values. Deserializing keeps any other value in
+        /// `Unknown` instead of failing.
+        impl std::str::FromStr for $name {
+            type Err = crate::error::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $( $wire => Ok($name::$variant), )+
+                    other => {
+                        let msg = format!("Invalid {}: {}", stringify!($name), other);
+                        Err(crate::error::Error::new(crate::error::ErrorType::ParseError, &msg))
+                    }
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        /// Unlike `FromStr`, never fails on an unlisted value, which becomes `Unknown`.
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = String::deserialize(deserializer)?;
+                Ok(value.parse().unwrap_or_else(|_| $name::Unknown(value)))
+            }
+        }
+    };
+}
@@ -0,0 +1,93 @@
+use crate::client::AsyncClearstreetClient;
+#[cfg(feature = "sync")]
+use crate::client::SyncClearstreetClient;
+use crate::error::Error;
+use crate::orders::create::{CreateOrderParams, CreateOrderResponse};
+use futures_util::StreamExt;
+
+/// Requests in flight at once when the client trait batch methods are used.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+// The Studio API takes one order per request, so batches are fanned out client side.
+
+/// Submits every order with at most `concurrency` requests in flight. Results are in input order.
+pub async fn create_orders<C>(
+    client: &C,
+    orders: Vec<CreateOrderParams>,
+    concurrency: usize,
+) -> Vec<Result<CreateOrderResponse, Error>>
+where
+    C: AsyncClearstreetClient + ?Sized,
+{
+    futures_util::stream::iter(orders)
+        .map(|params| client.create_order(params))
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Cancels every order with at most `concurrency` requests in flight. Results are in input order.
+pub async fn cancel_orders<C>(
+    client: &C,
+    order_ids: Vec<String>,
+    concurrency: usize,
+) -> Vec<Result<(), Error>>
+where
+    C: AsyncClearstreetClient + ?Sized,
+{
+    futures_util::stream::iter(order_ids)
+        .map(|order_id| async move { client.delete_order(&order_id).await })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(feature = "sync")]
+fn fan_out_blocking<T, R, F>(items: Vec<T>, concurrency: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let total = items.len();
+    let queue = std::sync::Mutex::new(items.into_iter().enumerate());
+    let results = std::sync::Mutex::new((0..total).map(|_| None).collect::<Vec<Option<R>>>());
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, total.max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let next = queue.lock().unwrap().next();
+                    let Some((index, item)) = next else { break };
+                    let result = f(item);
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    // The scope re-raises worker panics, so every slot is filled here.
+    results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+/// Blocking [`create_orders`], using up to `concurrency` threads.
+#[cfg(feature = "sync")]
+pub fn create_orders_blocking<C>(
+    client: &C,
+    orders: Vec<CreateOrderParams>,
+    concurrency: usize,
+) -> Vec<Result<CreateOrderResponse, Error>>
+where
+    C: SyncClearstreetClient + ?Sized,
+{
+    fan_out_blocking(orders, concurrency, |params| client.create_order(params))
+}
+
+/// Blocking [`cancel_orders`], using up to `concurrency` threads.
+#[cfg(feature = "sync")]
+pub fn cancel_orders_blocking<C>(client: &C, order_ids: Vec<String>, concurrency: usize) -> Vec<Result<(), Error>>
+where
+    C: SyncClearstreetClient + ?Sized,
+{
+    fan_out_blocking(order_ids, concurrency, |order_id| client.delete_order(&order_id))
+}
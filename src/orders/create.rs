@@ -1,4 +1,5 @@
 use crate::error::{Error, ErrorType};
+use crate::instruments::option_symbol::OptionSymbol;
 use crate::orders::strategy::Strategy;
 use crate::orders::{OrderSide, OrderType, SymbolFormat, TimeInForce};
 use crate::utils::{parse_response};
@@ -27,6 +28,56 @@ pub struct CreateOrderParams {
     pub symbol_format: SymbolFormat,
     pub strategy: Strategy,
 }
+
+impl CreateOrderParams {
+    /// Day market order for `contracts` of an option, sent with its OSI symbol.
+    pub fn option_market(
+        account_id: &str,
+        reference_id: &str,
+        option: &OptionSymbol,
+        side: OrderSide,
+        contracts: u32,
+    ) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            reference_id: reference_id.to_string(),
+            order_type: OrderType::Market,
+            order_side: side,
+            quantity: contracts.to_string(),
+            price: None,
+            stop_price: None,
+            time_in_force: TimeInForce::Day,
+            symbol: option.to_osi(),
+            symbol_format: SymbolFormat::Osi,
+            strategy: Strategy::default(),
+        }
+    }
+
+    /// Day limit order for `contracts` of an option at a per-share `price`.
+    pub fn option_limit(
+        account_id: &str,
+        reference_id: &str,
+        option: &OptionSymbol,
+        side: OrderSide,
+        contracts: u32,
+        price: &str,
+    ) -> Self {
+        Self {
+            order_type: OrderType::Limit,
+            price: Some(price.to_string()),
+            ..Self::option_market(account_id, reference_id, option, side, contracts)
+        }
+    }
+
+    /// The option contract, if `symbol` is an OSI option symbol.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        match self.symbol_format {
+            SymbolFormat::Osi => OptionSymbol::from_osi(&self.symbol).ok(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateOrderResponse {
     pub order_id: String,
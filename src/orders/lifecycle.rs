@@ -0,0 +1,105 @@
+use crate::error::{Error, ErrorType};
+use crate::orders::{Order, OrderState, OrderStatus};
+
+impl OrderStatus {
+    /// No further updates are expected, other than repeats of the same status.
+    ///
+    /// `DoneForDay` is not terminal, since multi-day orders resume in the next session.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled | OrderStatus::Canceled | OrderStatus::Rejected | OrderStatus::Expired
+        )
+    }
+
+    /// Accepted by the venue and able to trade.
+    pub fn is_working(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::New
+                | OrderStatus::PartiallyFilled
+                | OrderStatus::Replaced
+                | OrderStatus::PendingCancel
+                | OrderStatus::PendingReplace
+                | OrderStatus::Stopped
+                | OrderStatus::Calculated
+                | OrderStatus::AcceptedForBidding
+        )
+    }
+
+    /// Not terminal and not already being canceled. False for an unrecognised status.
+    pub fn is_cancelable(&self) -> bool {
+        !self.is_terminal() && !matches!(self, OrderStatus::PendingCancel | OrderStatus::Unknown(_))
+    }
+
+    pub fn can_replace(&self) -> bool {
+        self.is_working() && !matches!(self, OrderStatus::PendingCancel | OrderStatus::PendingReplace)
+    }
+
+    /// Whether an order in this status may next be reported as `next`.
+    ///
+    /// Transitions into or out of an unrecognised status cannot be checked and are refused.
+    pub fn can_transition_to(&self, next: &OrderStatus) -> bool {
+        if self == next {
+            return true;
+        }
+        if self.is_terminal() || self.is_unknown() || next.is_unknown() {
+            return false;
+        }
+
+        match next {
+            OrderStatus::PendingNew => false,
+            // Filled quantity never goes back to zero.
+            OrderStatus::New => *self != OrderStatus::PartiallyFilled,
+            _ => true,
+        }
+    }
+}
+
+impl OrderState {
+    /// Closed or rejected. An unrecognised state is treated as still open.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderState::Closed | OrderState::Rejected)
+    }
+
+    pub fn can_transition_to(&self, next: &OrderState) -> bool {
+        self == next || (*self == OrderState::Open && !next.is_unknown())
+    }
+}
+
+impl Order {
+    pub fn is_terminal(&self) -> bool {
+        self.state.is_terminal() || self.status.is_terminal()
+    }
+
+    pub fn is_working(&self) -> bool {
+        !self.state.is_terminal() && self.status.is_working()
+    }
+
+    pub fn is_cancelable(&self) -> bool {
+        !self.state.is_terminal() && self.status.is_cancelable()
+    }
+
+    pub fn can_replace(&self) -> bool {
+        !self.state.is_terminal() && self.status.can_replace()
+    }
+}
+
+/// Checks that `next` is a legal successor of `previous` for the same order.
+pub fn validate_transition(previous: &Order, next: &Order) -> Result<(), Error> {
+    if previous.status.can_transition_to(&next.status) && previous.state.can_transition_to(&next.state) {
+        return Ok(());
+    }
+
+    let msg = format!(
+        "Illegal transition for order {}: {:?}/{:?} (version {}) -> {:?}/{:?} (version {})",
+        next.order_id,
+        previous.state,
+        previous.status,
+        previous.version,
+        next.state,
+        next.status,
+        next.version
+    );
+    Err(Error::new(ErrorType::InvalidStateTransition, &msg))
+}
@@ -0,0 +1,135 @@
+use crate::client::AsyncClearstreetClient;
+use crate::error::{Error, ErrorType};
+use crate::orders::update::UpdateOrderRequestBody;
+use crate::orders::wait::wait_for_status;
+use crate::orders::{Order, OrderStatus};
+use crate::websockets::broadcast::ActivityBroadcaster;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Fails unless `order` is still at `expected_version` and can be replaced.
+pub fn check_replaceable(order: &Order, expected_version: i64) -> Result<(), Error> {
+    if order.version != expected_version {
+        let msg = format!(
+            "Order {} is at version {}, expected {}",
+            order.order_id, order.version, expected_version
+        );
+        return Err(Error::new(ErrorType::VersionConflict, &msg));
+    }
+
+    if !order.can_replace() {
+        let msg = format!(
+            "Order {} cannot be replaced in {:?}/{:?}",
+            order.order_id, order.state, order.status
+        );
+        return Err(Error::new(ErrorType::InvalidStateTransition, &msg));
+    }
+
+    Ok(())
+}
+
+// Compares decimal strings by value, so "100" matches "100.00".
+fn same_value(a: &str, b: &str) -> bool {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => (a - b).abs() < 1e-9,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+fn same_optional_value(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => same_value(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_applied(order: &Order, params: &UpdateOrderRequestBody) -> bool {
+    order.status == OrderStatus::Replaced
+        || (same_value(&order.quantity, &params.quantity)
+            && same_optional_value(&order.price, &params.price)
+            && same_optional_value(&order.stop_price, &params.stop_price))
+}
+
+/// The broker's explanation for the order's last update, if it gave one.
+fn update_reason(order: &Order) -> Option<&str> {
+    [&order.text, &order.order_update_reason]
+        .into_iter()
+        .map(|reason| reason.trim())
+        .find(|reason| !reason.is_empty())
+}
+
+/// Replaces an order if it is still at `expected_version`, then waits for the outcome.
+///
+/// The version check only catches updates that arrived before the call; one landing
+/// between the check and the `update_order` request is not detected. Resolves with the
+/// order once it reports `Replaced` or the new terms. Fails with `OrderRejected` when it
+/// leaves `PendingReplace` with the old terms, and with `OrderClosed` if it fills or
+/// closes before the replace is applied.
+///
+/// A reject can return the order to its old terms before `PendingReplace` is seen. On
+/// timeout the order is fetched once more, and an update with the old terms and no
+/// new fills, or with a reason from the broker, is reported as `OrderRejected`.
+pub async fn replace_order(
+    client: &dyn AsyncClearstreetClient,
+    feed: Option<&ActivityBroadcaster>,
+    order_id: &str,
+    expected_version: i64,
+    params: UpdateOrderRequestBody,
+    timeout: Duration,
+) -> Result<Order, Error> {
+    let current = client.get_order(order_id).await?;
+    check_replaceable(&current, expected_version)?;
+
+    client.update_order(order_id, params.clone()).await?;
+
+    // Leaving PendingReplace with the old terms is a rejection, unless a fill explains the update.
+    let seen_pending = AtomicBool::new(false);
+    let outcome = |order: &Order| {
+        if order.version <= expected_version {
+            return false;
+        }
+        if order.status == OrderStatus::PendingReplace {
+            seen_pending.store(true, Ordering::Relaxed);
+            return false;
+        }
+        let reverted = seen_pending.load(Ordering::Relaxed)
+            && same_value(&order.filled_quantity, &current.filled_quantity);
+        is_applied(order, &params) || order.is_terminal() || reverted
+    };
+
+    let order = match wait_for_status(client, feed, order_id, outcome, timeout).await {
+        Ok(completion) => completion.order,
+        Err(e) if e.error_type == ErrorType::TimeoutError => {
+            let order = client.get_order(order_id).await?;
+            let explained =
+                same_value(&order.filled_quantity, &current.filled_quantity) || update_reason(&order).is_some();
+            if order.version <= expected_version || order.status == OrderStatus::PendingReplace || !explained {
+                return Err(e);
+            }
+            order
+        }
+        Err(e) => return Err(e),
+    };
+
+    if is_applied(&order, &params) {
+        return Ok(order);
+    }
+
+    if order.is_terminal() {
+        let msg = format!(
+            "Order {} became {:?}/{:?} before the replace was applied",
+            order_id, order.state, order.status
+        );
+        return Err(Error::new(ErrorType::OrderClosed, &msg));
+    }
+
+    let mut msg = format!(
+        "Replace of order {} was not applied, order is {:?} at version {}",
+        order_id, order.status, order.version
+    );
+    if let Some(reason) = update_reason(&order) {
+        msg.push_str(&format!(": {}", reason));
+    }
+    Err(Error::new(ErrorType::OrderRejected, &msg))
+}
@@ -0,0 +1,200 @@
+use crate::client::AsyncClearstreetClient;
+#[cfg(feature = "sync")]
+use crate::client::SyncClearstreetClient;
+use crate::error::{Error, ErrorType};
+use crate::instruments::option_symbol::OptionSymbol;
+use crate::orders::batch::DEFAULT_BATCH_CONCURRENCY;
+use crate::orders::create::{CreateOrderParams, CreateOrderResponse};
+use crate::orders::strategy::Strategy;
+use crate::orders::{OrderSide, OrderType, SymbolFormat, TimeInForce};
+
+// The Studio API takes one symbol per order, so each leg of a spread is its own order.
+
+/// One leg of a spread, `ratio` units for every unit of the spread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpreadLeg {
+    pub symbol: String,
+    pub symbol_format: SymbolFormat,
+    pub side: OrderSide,
+    pub ratio: u32,
+    /// Limit price for this leg, sent as a market order when `None`.
+    pub price: Option<String>,
+}
+
+impl SpreadLeg {
+    /// An option leg, sent with its OSI symbol.
+    pub fn option(option: &OptionSymbol, side: OrderSide, ratio: u32) -> Self {
+        Self {
+            symbol: option.to_osi(),
+            symbol_format: SymbolFormat::Osi,
+            side,
+            ratio,
+            price: None,
+        }
+    }
+
+    /// A stock leg, such as the shares of a covered call.
+    pub fn equity(symbol: &str, side: OrderSide, ratio: u32) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            symbol_format: SymbolFormat::Cms,
+            side,
+            ratio,
+            price: None,
+        }
+    }
+
+    pub fn limit(mut self, price: &str) -> Self {
+        self.price = Some(price.to_string());
+        self
+    }
+}
+
+/// A multi-leg order, such as a vertical spread or a covered call.
+///
+/// Legs are placed as separate orders and fill independently; the API has no
+/// atomic multi-leg order.
+#[derive(Debug, Clone)]
+pub struct SpreadOrder {
+    pub account_id: String,
+    /// Each leg is sent with `{reference_id}-{n}`, numbered from 1.
+    pub reference_id: String,
+    /// Units of the spread. Each leg trades `quantity * ratio`.
+    pub quantity: u32,
+    pub legs: Vec<SpreadLeg>,
+    pub time_in_force: TimeInForce,
+    pub strategy: Strategy,
+}
+
+impl SpreadOrder {
+    pub fn new(account_id: &str, reference_id: &str, quantity: u32) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            reference_id: reference_id.to_string(),
+            quantity,
+            legs: Vec::new(),
+            time_in_force: TimeInForce::Day,
+            strategy: Strategy::default(),
+        }
+    }
+
+    pub fn leg(mut self, leg: SpreadLeg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// The order sent for each leg, in leg order.
+    pub fn leg_params(&self) -> Result<Vec<CreateOrderParams>, Error> {
+        if self.legs.is_empty() || self.quantity == 0 {
+            let msg = format!("Spread {} needs at least one leg and a quantity", self.reference_id);
+            return Err(Error::new(ErrorType::ValidationError, &msg));
+        }
+
+        self.legs
+            .iter()
+            .enumerate()
+            .map(|(index, leg)| {
+                if leg.ratio == 0 {
+                    let msg = format!("Leg {} of spread {} has a zero ratio", index + 1, self.reference_id);
+                    return Err(Error::new(ErrorType::ValidationError, &msg));
+                }
+
+                Ok(CreateOrderParams {
+                    account_id: self.account_id.clone(),
+                    reference_id: format!("{}-{}", self.reference_id, index + 1),
+                    order_type: if leg.price.is_some() { OrderType::Limit } else { OrderType::Market },
+                    order_side: leg.side.clone(),
+                    quantity: (u64::from(self.quantity) * u64::from(leg.ratio)).to_string(),
+                    price: leg.price.clone(),
+                    stop_price: None,
+                    time_in_force: self.time_in_force.clone(),
+                    symbol: leg.symbol.clone(),
+                    symbol_format: leg.symbol_format.clone(),
+                    strategy: self.strategy.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits leg results into the placed order ids and the first failure, if any.
+fn collect_legs(
+    reference_id: &str,
+    results: Vec<Result<CreateOrderResponse, Error>>,
+) -> (Vec<CreateOrderResponse>, Option<Error>) {
+    let mut placed = Vec::new();
+    let mut failure = None;
+
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(response) => placed.push(response),
+            Err(e) if failure.is_none() => {
+                let msg = format!("Leg {} of spread {} failed: {}", index + 1, reference_id, e.message);
+                failure = Some(Error::new(e.error_type, &msg));
+            }
+            Err(_) => {}
+        }
+    }
+
+    (placed, failure)
+}
+
+/// Places every leg of `spread`. Results are in leg order.
+///
+/// If any leg fails, the legs already placed are canceled and the first failure is
+/// returned. A leg may fill before it is canceled.
+pub async fn create_spread_order(
+    client: &dyn AsyncClearstreetClient,
+    spread: &SpreadOrder,
+) -> Result<Vec<CreateOrderResponse>, Error> {
+    let legs = spread.leg_params()?;
+    let results = crate::orders::batch::create_orders(client, legs, DEFAULT_BATCH_CONCURRENCY).await;
+
+    let (placed, failure) = collect_legs(&spread.reference_id, results);
+    let Some(failure) = failure else {
+        return Ok(placed);
+    };
+
+    let order_ids = placed.into_iter().map(|response| response.order_id).collect();
+    for result in crate::orders::batch::cancel_orders(client, order_ids, DEFAULT_BATCH_CONCURRENCY).await {
+        if let Err(e) = result {
+            tracing::warn!("Failed to cancel a leg of spread {}: {}", spread.reference_id, e);
+        }
+    }
+
+    Err(failure)
+}
+
+/// Blocking [`create_spread_order`].
+#[cfg(feature = "sync")]
+pub fn create_spread_order_blocking(
+    client: &dyn SyncClearstreetClient,
+    spread: &SpreadOrder,
+) -> Result<Vec<CreateOrderResponse>, Error> {
+    let legs = spread.leg_params()?;
+    let results = crate::orders::batch::create_orders_blocking(client, legs, DEFAULT_BATCH_CONCURRENCY);
+
+    let (placed, failure) = collect_legs(&spread.reference_id, results);
+    let Some(failure) = failure else {
+        return Ok(placed);
+    };
+
+    let order_ids = placed.into_iter().map(|response| response.order_id).collect();
+    for result in crate::orders::batch::cancel_orders_blocking(client, order_ids, DEFAULT_BATCH_CONCURRENCY) {
+        if let Err(e) = result {
+            tracing::warn!("Failed to cancel a leg of spread {}: {}", spread.reference_id, e);
+        }
+    }
+
+    Err(failure)
+}
@@ -1,4 +1,5 @@
 use crate::error::{Error, ErrorType};
+use crate::instruments::AssetClass;
 use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
@@ -6,42 +7,19 @@ use std::fmt;
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
-#[serde(rename_all = "snake_case")]
-pub enum Urgency {
-    #[serde(rename = "super-passive")]
-    SuperPassive,
-    #[serde(rename = "passive")]
-    Passive,
-    #[serde(rename = "moderate")]
-    Moderate,
-    #[serde(rename = "aggressive")]
-    Aggressive,
-    #[serde(rename = "super-aggressive")]
-    SuperAggressive,
-}
-
-impl FromStr for Urgency {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "super-passive" => Ok(Urgency::SuperPassive),
-            "passive" => Ok(Urgency::Passive),
-            "moderate" => Ok(Urgency::Moderate),
-            "aggressive" => Ok(Urgency::Aggressive),
-            "super-aggressive" => Ok(Urgency::SuperAggressive),
-            other => Err(Error::new(
-                ErrorType::ParseError,
-                &format!("Invalid Urgency: {}", other),
-            )),
-        }
+wire_enum! {
+    pub enum Urgency {
+        SuperPassive => "super-passive",
+        Passive => "passive",
+        Moderate => "moderate",
+        Aggressive => "aggressive",
+        SuperAggressive => "super-aggressive",
     }
 }
 
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
-#[serde(rename_all = "snake_case")]
+/// A DMA venue, identified on the wire by its lowercase MIC.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Destination {
     Arcx, // NYSE ARCA
     Bats, // BATS Exchange
@@ -56,11 +34,74 @@ pub enum Destination {
     Xcis, // NYSE National
     Xnms, // NASDAQ/NMS (Global Market)
     Xnys, // New York Stock Exchange
+    Xchi, // NYSE Chicago
+    Xpsx, // NASDAQ PSX
+    Ltse, // Long-Term Stock Exchange
+    Amxo, // NYSE American Options
+    Arco, // NYSE Arca Options
+    Bato, // Cboe BZX Options
+    C2ox, // Cboe C2 Options
+    Edgo, // Cboe EDGX Options
+    Emld, // MIAX Emerald
+    Gmni, // NASDAQ GEMX
+    Mcry, // NASDAQ MRX
+    Mprl, // MIAX Pearl Options
+    Sphr, // MIAX Sapphire
+    Xbox, // BOX Options Exchange
+    Xbxo, // NASDAQ BX Options
+    Xcbo, // Cboe Options Exchange
+    Xise, // NASDAQ ISE
+    Xmio, // MIAX Options
+    Xndq, // NASDAQ Options Market
+    Xphl, // NASDAQ PHLX
+    /// A venue this SDK does not know yet, kept as sent by the API.
+    Unknown(String),
 }
 
-impl Display for Destination {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
+const EQUITIES: &[AssetClass] = &[AssetClass::Equity];
+const OPTIONS: &[AssetClass] = &[AssetClass::Option];
+
+impl Destination {
+    /// Every known venue.
+    pub const ALL: &'static [Destination] = &[
+        Destination::Arcx,
+        Destination::Bats,
+        Destination::Baty,
+        Destination::Edga,
+        Destination::Edgx,
+        Destination::Eprl,
+        Destination::Iexg,
+        Destination::Memx,
+        Destination::Xase,
+        Destination::Xbos,
+        Destination::Xcis,
+        Destination::Xnms,
+        Destination::Xnys,
+        Destination::Xchi,
+        Destination::Xpsx,
+        Destination::Ltse,
+        Destination::Amxo,
+        Destination::Arco,
+        Destination::Bato,
+        Destination::C2ox,
+        Destination::Edgo,
+        Destination::Emld,
+        Destination::Gmni,
+        Destination::Mcry,
+        Destination::Mprl,
+        Destination::Sphr,
+        Destination::Xbox,
+        Destination::Xbxo,
+        Destination::Xcbo,
+        Destination::Xise,
+        Destination::Xmio,
+        Destination::Xndq,
+        Destination::Xphl,
+    ];
+
+    /// The value sent to the API.
+    pub fn code(&self) -> &str {
+        match self {
             Destination::Arcx => "arcx",
             Destination::Bats => "bats",
             Destination::Baty => "baty",
@@ -74,8 +115,196 @@ impl Display for Destination {
             Destination::Xcis => "xcis",
             Destination::Xnms => "xnms",
             Destination::Xnys => "xnys",
-        };
-        write!(f, "{}", s)
+            Destination::Xchi => "xchi",
+            Destination::Xpsx => "xpsx",
+            Destination::Ltse => "ltse",
+            Destination::Amxo => "amxo",
+            Destination::Arco => "arco",
+            Destination::Bato => "bato",
+            Destination::C2ox => "c2ox",
+            Destination::Edgo => "edgo",
+            Destination::Emld => "emld",
+            Destination::Gmni => "gmni",
+            Destination::Mcry => "mcry",
+            Destination::Mprl => "mprl",
+            Destination::Sphr => "sphr",
+            Destination::Xbox => "xbox",
+            Destination::Xbxo => "xbxo",
+            Destination::Xcbo => "xcbo",
+            Destination::Xise => "xise",
+            Destination::Xmio => "xmio",
+            Destination::Xndq => "xndq",
+            Destination::Xphl => "xphl",
+            Destination::Unknown(code) => code,
+        }
+    }
+
+    /// ISO 10383 market identifier code.
+    pub fn mic(&self) -> String {
+        self.code().to_uppercase()
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Destination::Arcx => "NYSE Arca",
+            Destination::Bats => "Cboe BZX Exchange",
+            Destination::Baty => "Cboe BYX Exchange",
+            Destination::Edga => "Cboe EDGA Exchange",
+            Destination::Edgx => "Cboe EDGX Exchange",
+            Destination::Eprl => "MIAX Pearl Equities",
+            Destination::Iexg => "Investors Exchange",
+            Destination::Memx => "Members Exchange",
+            Destination::Xase => "NYSE American",
+            Destination::Xbos => "Nasdaq BX",
+            Destination::Xcis => "NYSE National",
+            Destination::Xnms => "Nasdaq Global Market",
+            Destination::Xnys => "New York Stock Exchange",
+            Destination::Xchi => "NYSE Chicago",
+            Destination::Xpsx => "Nasdaq PSX",
+            Destination::Ltse => "Long-Term Stock Exchange",
+            Destination::Amxo => "NYSE American Options",
+            Destination::Arco => "NYSE Arca Options",
+            Destination::Bato => "Cboe BZX Options",
+            Destination::C2ox => "Cboe C2 Options",
+            Destination::Edgo => "Cboe EDGX Options",
+            Destination::Emld => "MIAX Emerald",
+            Destination::Gmni => "Nasdaq GEMX",
+            Destination::Mcry => "Nasdaq MRX",
+            Destination::Mprl => "MIAX Pearl Options",
+            Destination::Sphr => "MIAX Sapphire",
+            Destination::Xbox => "BOX Options Exchange",
+            Destination::Xbxo => "Nasdaq BX Options",
+            Destination::Xcbo => "Cboe Options Exchange",
+            Destination::Xise => "Nasdaq ISE",
+            Destination::Xmio => "MIAX Options",
+            Destination::Xndq => "Nasdaq Options Market",
+            Destination::Xphl => "Nasdaq PHLX",
+            Destination::Unknown(code) => code,
+        }
+    }
+
+    /// Asset classes routable to this venue. Empty for unknown venues.
+    pub fn asset_classes(&self) -> &'static [AssetClass] {
+        match self {
+            Destination::Arcx
+            | Destination::Bats
+            | Destination::Baty
+            | Destination::Edga
+            | Destination::Edgx
+            | Destination::Eprl
+            | Destination::Iexg
+            | Destination::Memx
+            | Destination::Xase
+            | Destination::Xbos
+            | Destination::Xcis
+            | Destination::Xnms
+            | Destination::Xnys
+            | Destination::Xchi
+            | Destination::Xpsx
+            | Destination::Ltse => EQUITIES,
+            Destination::Unknown(_) => &[],
+            _ => OPTIONS,
+        }
+    }
+
+    pub fn supports(&self, asset_class: &AssetClass) -> bool {
+        self.asset_classes().contains(asset_class)
+    }
+}
+
+impl FromStr for Destination {
+    type Err = Error;
+
+    /// Accepts the wire code or the MIC in any case. Unrecognised codes become `Unknown`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_lowercase();
+        if code.is_empty() {
+            return Err(Error::new(ErrorType::ParseError, "Invalid Destination: empty"));
+        }
+
+        Ok(Destination::ALL
+            .iter()
+            .find(|destination| destination.code() == code)
+            .cloned()
+            .unwrap_or(Destination::Unknown(code)))
+    }
+}
+
+impl Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl Serialize for Destination {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Destination {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        Destination::from_str(&code).map_err(|e| de::Error::custom(e.message))
+    }
+}
+
+/// A percentage of traded volume. Always finite, so strategies can be compared with `Eq`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Percent(f64);
+
+impl Eq for Percent {}
+
+impl Percent {
+    pub fn new(value: f64) -> Result<Self, Error> {
+        if !value.is_finite() {
+            let msg = format!("Invalid Percent: {}", value);
+            return Err(Error::new(ErrorType::ParseError, &msg));
+        }
+        Ok(Percent(value))
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Percent {
+    type Error = Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Percent::new(value)
+    }
+}
+
+impl Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Percent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Percent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = f64::deserialize(deserializer)?;
+        Percent::new(value).map_err(|e| de::Error::custom(e.message))
     }
 }
 
@@ -89,6 +318,38 @@ pub enum Strategy {
     DirectMarketAccess {
         destination: Destination
     },
+    Vwap {
+        start_at: Option<i64>,
+        end_at: Option<i64>,
+        urgency: Option<Urgency>,
+        max_percent: Option<Percent>,
+    },
+    Twap {
+        start_at: Option<i64>,
+        end_at: Option<i64>,
+        urgency: Option<Urgency>,
+        max_percent: Option<Percent>,
+    },
+    PercentOfVolume {
+        start_at: Option<i64>,
+        end_at: Option<i64>,
+        urgency: Option<Urgency>,
+        /// Participation rate to aim for, in percent of traded volume.
+        target_percent: Percent,
+        min_percent: Option<Percent>,
+        max_percent: Option<Percent>,
+    },
+    ArrivalPrice {
+        start_at: Option<i64>,
+        end_at: Option<i64>,
+        urgency: Option<Urgency>,
+        max_percent: Option<Percent>,
+    },
+    /// A strategy this SDK does not model yet, kept as sent by the API.
+    Other {
+        strategy_type: String,
+        fields: serde_json::Map<String, serde_json::Value>,
+    },
 }
 
 impl Default for Strategy {
@@ -101,152 +362,199 @@ impl Default for Strategy {
     }
 }
 
+impl Strategy {
+    /// The API's `type` string.
+    pub fn strategy_type(&self) -> &str {
+        match self {
+            Strategy::SmartOrderRoute { .. } => "sor",
+            Strategy::DirectMarketAccess { .. } => "dma",
+            Strategy::Vwap { .. } => "vwap",
+            Strategy::Twap { .. } => "twap",
+            Strategy::PercentOfVolume { .. } => "pov",
+            Strategy::ArrivalPrice { .. } => "ap",
+            Strategy::Other { strategy_type, .. } => strategy_type,
+        }
+    }
+}
+
+fn serialize_optional<M, T>(map: &mut M, key: &str, value: &Option<T>) -> Result<(), M::Error>
+where
+    M: SerializeMap,
+    T: Serialize,
+{
+    match value {
+        Some(value) => map.serialize_entry(key, value),
+        None => Ok(()),
+    }
+}
+
 impl Serialize for Strategy {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", self.strategy_type())?;
 
         match self {
             Strategy::SmartOrderRoute {
                 start_at,
                 end_at,
-                urgency, ..
+                urgency,
             } => {
-                map.serialize_entry("type", "sor")?;
-
-                if let Some(start_at) = start_at {
-                    map.serialize_entry("start_at", start_at)?;
-                }
-                if let Some(end_at) = end_at {
-                    map.serialize_entry("end_at", end_at)?;
-                }
-                if let Some(urgency) = urgency {
-                    map.serialize_entry("urgency", urgency)?;
-                }
-            },
+                serialize_optional(&mut map, "start_at", start_at)?;
+                serialize_optional(&mut map, "end_at", end_at)?;
+                serialize_optional(&mut map, "urgency", urgency)?;
+            }
             Strategy::DirectMarketAccess {
                 destination,
             } => {
-                map.serialize_entry("type", "dma")?;
                 map.serialize_entry("destination", &destination.to_string())?;
             }
+            Strategy::Vwap {
+                start_at,
+                end_at,
+                urgency,
+                max_percent,
+            }
+            | Strategy::Twap {
+                start_at,
+                end_at,
+                urgency,
+                max_percent,
+            }
+            | Strategy::ArrivalPrice {
+                start_at,
+                end_at,
+                urgency,
+                max_percent,
+            } => {
+                serialize_optional(&mut map, "start_at", start_at)?;
+                serialize_optional(&mut map, "end_at", end_at)?;
+                serialize_optional(&mut map, "urgency", urgency)?;
+                serialize_optional(&mut map, "max_percent", max_percent)?;
+            }
+            Strategy::PercentOfVolume {
+                start_at,
+                end_at,
+                urgency,
+                target_percent,
+                min_percent,
+                max_percent,
+            } => {
+                serialize_optional(&mut map, "start_at", start_at)?;
+                serialize_optional(&mut map, "end_at", end_at)?;
+                serialize_optional(&mut map, "urgency", urgency)?;
+                map.serialize_entry("target_percent", target_percent)?;
+                serialize_optional(&mut map, "min_percent", min_percent)?;
+                serialize_optional(&mut map, "max_percent", max_percent)?;
+            }
+            Strategy::Other { fields, .. } => {
+                for (key, value) in fields.iter().filter(|(key, _)| key.as_str() != "type") {
+                    map.serialize_entry(key, value)?;
+                }
+            }
         }
 
         map.end()
     }
 }
 
+/// Removes `key` from the strategy map, treating `null` as absent.
+fn take_field<T, E>(fields: &mut serde_json::Map<String, serde_json::Value>, key: &'static str) -> Result<Option<T>, E>
+where
+    T: de::DeserializeOwned,
+    E: de::Error,
+{
+    match fields.remove(key) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| de::Error::custom(format!("invalid {}: {}", key, e))),
+    }
+}
 
-impl<'de> Deserialize<'de> for Strategy {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        enum Field {
-            Type,
-            StartAt,
-            EndAt,
-            Urgency,
-            Unknown,
-            Destination,
-        }
-
-        impl<'de> Deserialize<'de> for Field {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: Deserializer<'de>,
-            {
-                deserializer.deserialize_identifier(FieldVisitor)
-            }
-        }
-
-        struct FieldVisitor;
+struct StrategyVisitor;
 
-        impl<'de> Visitor<'de> for FieldVisitor {
-            type Value = Field;
+impl<'de> Visitor<'de> for StrategyVisitor {
+    type Value = Strategy;
 
-            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                f.write_str("a valid strategy field")
-            }
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a valid strategy map")
+    }
 
-            fn visit_str<E>(self, v: &str) -> Result<Field, E>
-            where
-                E: de::Error,
-            {
-                Ok(match v {
-                    "type" => Field::Type,
-                    "start_at" => Field::StartAt,
-                    "end_at" => Field::EndAt,
-                    "urgency" => Field::Urgency,
-                    "destination" => Field::Destination,
-                    _ => Field::Unknown,
-                })
-            }
+    fn visit_map<M>(self, mut map: M) -> Result<Strategy, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut fields = serde_json::Map::new();
+        while let Some((key, value)) = map.next_entry::<String, serde_json::Value>()? {
+            fields.insert(key, value);
         }
 
-        struct StrategyVisitor;
+        let strategy_type: String =
+            take_field(&mut fields, "type")?.ok_or_else(|| de::Error::missing_field("type"))?;
 
-        impl<'de> Visitor<'de> for StrategyVisitor {
-            type Value = Strategy;
-
-            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                f.write_str("a valid strategy map")
+        let strategy = match strategy_type.as_str() {
+            "sor" => Strategy::SmartOrderRoute {
+                start_at: take_field(&mut fields, "start_at")?,
+                end_at: take_field(&mut fields, "end_at")?,
+                urgency: take_field(&mut fields, "urgency")?,
+            },
+            "dma" => Strategy::DirectMarketAccess {
+                destination: take_field(&mut fields, "destination")?
+                    .ok_or_else(|| de::Error::missing_field("destination"))?,
+            },
+            "vwap" => Strategy::Vwap {
+                start_at: take_field(&mut fields, "start_at")?,
+                end_at: take_field(&mut fields, "end_at")?,
+                urgency: take_field(&mut fields, "urgency")?,
+                max_percent: take_field(&mut fields, "max_percent")?,
+            },
+            "twap" => Strategy::Twap {
+                start_at: take_field(&mut fields, "start_at")?,
+                end_at: take_field(&mut fields, "end_at")?,
+                urgency: take_field(&mut fields, "urgency")?,
+                max_percent: take_field(&mut fields, "max_percent")?,
+            },
+            "pov" => Strategy::PercentOfVolume {
+                start_at: take_field(&mut fields, "start_at")?,
+                end_at: take_field(&mut fields, "end_at")?,
+                urgency: take_field(&mut fields, "urgency")?,
+                target_percent: take_field(&mut fields, "target_percent")?
+                    .ok_or_else(|| de::Error::missing_field("target_percent"))?,
+                min_percent: take_field(&mut fields, "min_percent")?,
+                max_percent: take_field(&mut fields, "max_percent")?,
+            },
+            "ap" => Strategy::ArrivalPrice {
+                start_at: take_field(&mut fields, "start_at")?,
+                end_at: take_field(&mut fields, "end_at")?,
+                urgency: take_field(&mut fields, "urgency")?,
+                max_percent: take_field(&mut fields, "max_percent")?,
+            },
+            _ => {
+                return Ok(Strategy::Other {
+                    strategy_type,
+                    fields,
+                });
             }
+        };
 
-            fn visit_map<M>(self, mut map: M) -> Result<Strategy, M::Error>
-            where
-                M: MapAccess<'de>,
-            {
-                let mut strategy_type: Option<String> = None;
-                let mut start_at: Option<i64> = None;
-                let mut end_at: Option<i64> = None;
-                let mut urgency: Option<Urgency> = None;
-                let mut destination: Option<Destination> = None;
-
-                while let Some(key) = map.next_key::<Field>()? {
-                    match key {
-                        Field::Type => {
-                            strategy_type = Some(map.next_value()?);
-                        }
-                        Field::StartAt => {
-                            start_at = Some(map.next_value()?);
-                        }
-                        Field::EndAt => {
-                            end_at = Some(map.next_value()?);
-                        }
-                        Field::Urgency => {
-                            urgency = Some(map.next_value()?);
-                        }
-                        Field::Destination => {
-                            destination = Some(map.next_value()?);
-                        }
-                        Field::Unknown => {
-                            let _: de::IgnoredAny = map.next_value()?;
-                        }
-                    }
-                }
-
-                match strategy_type.as_deref() {
-                    Some("sor") => Ok(Strategy::SmartOrderRoute {
-                        start_at,
-                        end_at,
-                        urgency,
-                    }),
-                    Some("dma") => {
-                        if let Some(dest) = destination {
-                            Ok(Strategy::DirectMarketAccess { destination: dest })
-                        } else {
-                            Err(de::Error::missing_field("destination"))
-                        }
-                    }
-                    Some(other) => Err(de::Error::custom(format!("unsupported strategy type: {}", other))),
-                    None => Err(de::Error::missing_field("type")),
-                }
-            }
+        // Keys added by the API to a known strategy are ignored, so orders still decode.
+        if !fields.is_empty() {
+            let keys: Vec<&String> = fields.keys().collect();
+            tracing::debug!("Ignoring unknown fields {:?} for strategy {}", keys, strategy_type);
         }
 
+        Ok(strategy)
+    }
+}
+
+impl<'de> Deserialize<'de> for Strategy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
         deserializer.deserialize_map(StrategyVisitor)
     }
 }
@@ -0,0 +1,254 @@
+use crate::client::AsyncClearstreetClient;
+use crate::error::Error;
+use crate::orders::get::ListOrdersParams;
+use crate::orders::lifecycle::validate_transition;
+use crate::orders::{Order, OrderState, OrderStatus};
+use crate::websockets::payloads::ActivityMessage;
+use crate::websockets::stream::ActivitySnapshot;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use tokio::sync::broadcast;
+
+const SEED_PAGE_SIZE: i64 = 100;
+// Oldest invalid transitions are dropped beyond this many.
+const MAX_INVALID_TRANSITIONS: usize = 256;
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum OrderChange {
+    Added(Order),
+    Updated { previous: Order, current: Order },
+}
+
+impl OrderChange {
+    /// The order as it is after the change.
+    pub fn order(&self) -> &Order {
+        match self {
+            OrderChange::Added(order) => order,
+            OrderChange::Updated { current, .. } => current,
+        }
+    }
+}
+
+/// An update that applied a status change the order lifecycle does not allow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub order_id: String,
+    pub version: i64,
+    pub from: (OrderState, OrderStatus),
+    pub to: (OrderState, OrderStatus),
+}
+
+/// Local view of the account's orders, kept current from `OrderUpdate` messages.
+///
+/// Updates are applied by `order_id`; anything with a `version` at or below the one
+/// already held is stale and discarded. Every applied change is published to
+/// [`OrderTracker::subscribe`] receivers. Illegal lifecycle transitions are still
+/// applied, since the broker is the source of truth, but are logged and kept in
+/// [`OrderTracker::invalid_transitions`], up to the most recent 256; use
+/// [`OrderTracker::try_apply`] to refuse them.
+pub struct OrderTracker {
+    orders: HashMap<String, Order>,
+    by_reference_id: HashMap<String, String>,
+    by_symbol: HashMap<String, BTreeSet<String>>,
+    changes: broadcast::Sender<OrderChange>,
+    invalid_transitions: VecDeque<InvalidTransition>,
+}
+
+impl Default for OrderTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(1024);
+
+        Self {
+            orders: HashMap::new(),
+            by_reference_id: HashMap::new(),
+            by_symbol: HashMap::new(),
+            changes,
+            invalid_transitions: VecDeque::new(),
+        }
+    }
+
+    /// Loads every order created between `from` and `to` (milliseconds) via `list_orders`.
+    pub async fn seed(&mut self, client: &dyn AsyncClearstreetClient, from: i64, to: i64) -> Result<usize, Error> {
+        let mut page_token = String::new();
+        let mut loaded = 0;
+
+        loop {
+            let response = client
+                .list_orders(ListOrdersParams {
+                    from,
+                    to,
+                    page_size: SEED_PAGE_SIZE,
+                    page_token,
+                })
+                .await?;
+
+            loaded += response.data.len();
+            for order in response.data {
+                self.apply(order);
+            }
+
+            match response.next_page_token {
+                Some(next) if !next.is_empty() => page_token = next,
+                _ => break,
+            }
+        }
+
+        tracing::debug!("Order tracker seeded with {} orders", loaded);
+        Ok(loaded)
+    }
+
+    /// Applies a newer version of an order. Returns `None` if the update was stale.
+    pub fn apply(&mut self, order: Order) -> Option<OrderChange> {
+        let change = match self.orders.get(&order.order_id) {
+            Some(existing) if order.version <= existing.version => {
+                tracing::trace!(
+                    "Discarding stale update for order {} (version {} <= {})",
+                    order.order_id,
+                    order.version,
+                    existing.version
+                );
+                return None;
+            }
+            Some(existing) => {
+                if let Err(e) = validate_transition(existing, &order) {
+                    tracing::warn!("{}", e);
+                    if self.invalid_transitions.len() >= MAX_INVALID_TRANSITIONS {
+                        self.invalid_transitions.pop_front();
+                    }
+                    self.invalid_transitions.push_back(InvalidTransition {
+                        order_id: order.order_id.clone(),
+                        version: order.version,
+                        from: (existing.state.clone(), existing.status.clone()),
+                        to: (order.state.clone(), order.status.clone()),
+                    });
+                }
+
+                OrderChange::Updated {
+                    previous: existing.clone(),
+                    current: order.clone(),
+                }
+            }
+            None => OrderChange::Added(order.clone()),
+        };
+
+        if let Some(reference_id) = &order.reference_id {
+            self.by_reference_id
+                .insert(reference_id.clone(), order.order_id.clone());
+        }
+        self.by_symbol
+            .entry(order.symbol.clone())
+            .or_default()
+            .insert(order.order_id.clone());
+        self.orders.insert(order.order_id.clone(), order);
+
+        // No receivers is fine, notifications are optional.
+        let _ = self.changes.send(change.clone());
+        Some(change)
+    }
+
+    /// Like [`OrderTracker::apply`], but refuses an update that breaks the order lifecycle.
+    pub fn try_apply(&mut self, order: Order) -> Result<Option<OrderChange>, Error> {
+        if let Some(existing) = self.orders.get(&order.order_id) {
+            if order.version > existing.version {
+                validate_transition(existing, &order)?;
+            }
+        }
+
+        Ok(self.apply(order))
+    }
+
+    /// Applies an `OrderUpdate`; other messages are ignored.
+    pub fn apply_message(&mut self, message: &ActivityMessage) -> Option<OrderChange> {
+        match message {
+            ActivityMessage::OrderUpdate(update) => self.apply(update.payload.data.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn apply_snapshot(&mut self, snapshot: &ActivitySnapshot) {
+        for order in &snapshot.orders {
+            self.apply(order.clone());
+        }
+    }
+
+    /// Recorded invalid transitions, oldest first.
+    pub fn invalid_transitions(&self) -> &VecDeque<InvalidTransition> {
+        &self.invalid_transitions
+    }
+
+    /// Returns and clears the recorded invalid transitions.
+    pub fn take_invalid_transitions(&mut self) -> Vec<InvalidTransition> {
+        std::mem::take(&mut self.invalid_transitions).into()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderChange> {
+        self.changes.subscribe()
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<&Order> {
+        self.orders.get(order_id)
+    }
+
+    pub fn get_by_reference_id(&self, reference_id: &str) -> Option<&Order> {
+        self.by_reference_id
+            .get(reference_id)
+            .and_then(|order_id| self.orders.get(order_id))
+    }
+
+    pub fn orders_for_symbol(&self, symbol: &str) -> Vec<&Order> {
+        self.by_symbol
+            .get(symbol)
+            .map(|order_ids| order_ids.iter().filter_map(|id| self.orders.get(id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Orders that are not terminal, including any in an unrecognised state.
+    pub fn open_orders(&self) -> Vec<&Order> {
+        self.orders
+            .values()
+            .filter(|order| !order.is_terminal())
+            .collect()
+    }
+
+    pub fn orders_by_status(&self, status: OrderStatus) -> Vec<&Order> {
+        self.orders
+            .values()
+            .filter(|order| order.status == status)
+            .collect()
+    }
+
+    pub fn orders(&self) -> impl Iterator<Item = &Order> {
+        self.orders.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Forgets an order, e.g. once it is closed and no longer of interest.
+    pub fn remove(&mut self, order_id: &str) -> Option<Order> {
+        let order = self.orders.remove(order_id)?;
+
+        if let Some(reference_id) = &order.reference_id {
+            self.by_reference_id.remove(reference_id);
+        }
+        if let Some(order_ids) = self.by_symbol.get_mut(&order.symbol) {
+            order_ids.remove(order_id);
+            if order_ids.is_empty() {
+                self.by_symbol.remove(&order.symbol);
+            }
+        }
+
+        Some(order)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::error::{Error, ErrorType};
+use crate::orders::create::CreateOrderParams;
+use crate::orders::strategy::{Percent, Strategy};
+use crate::orders::OrderType;
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorType::ValidationError, msg)
+}
+
+fn parse_positive(field: &str, value: &str) -> Result<f64, Error> {
+    match value.trim().parse::<f64>() {
+        Ok(parsed) if parsed > 0.0 && parsed.is_finite() => Ok(parsed),
+        _ => Err(invalid(&format!("{} must be a positive number, got '{}'", field, value))),
+    }
+}
+
+impl OrderType {
+    /// Whether orders of this type need a limit price. False for unknown types.
+    pub fn requires_price(&self) -> bool {
+        match self {
+            OrderType::Limit | OrderType::StopLimit => true,
+            OrderType::Market | OrderType::Stop | OrderType::Unknown(_) => false,
+        }
+    }
+
+    /// Whether orders of this type need a stop price. False for unknown types.
+    pub fn requires_stop_price(&self) -> bool {
+        match self {
+            OrderType::Stop | OrderType::StopLimit => true,
+            OrderType::Market | OrderType::Limit | OrderType::Unknown(_) => false,
+        }
+    }
+}
+
+fn validate_schedule(start_at: &Option<i64>, end_at: &Option<i64>) -> Result<(), Error> {
+    if let (Some(start_at), Some(end_at)) = (start_at, end_at) {
+        if start_at >= end_at {
+            return Err(invalid("Strategy start_at must be before end_at"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_percent(field: &str, value: Percent) -> Result<(), Error> {
+    if value.value() <= 0.0 || value.value() > 100.0 {
+        return Err(invalid(&format!("{} must be within (0, 100], got {}", field, value)));
+    }
+    Ok(())
+}
+
+pub fn validate_strategy(strategy: &Strategy) -> Result<(), Error> {
+    match strategy {
+        Strategy::SmartOrderRoute { start_at, end_at, .. } => validate_schedule(start_at, end_at),
+        Strategy::Vwap { start_at, end_at, max_percent, .. }
+        | Strategy::Twap { start_at, end_at, max_percent, .. }
+        | Strategy::ArrivalPrice { start_at, end_at, max_percent, .. } => {
+            validate_schedule(start_at, end_at)?;
+            if let Some(max_percent) = max_percent {
+                validate_percent("max_percent", *max_percent)?;
+            }
+            Ok(())
+        }
+        Strategy::PercentOfVolume {
+            start_at,
+            end_at,
+            target_percent,
+            min_percent,
+            max_percent,
+            ..
+        } => {
+            validate_schedule(start_at, end_at)?;
+            validate_percent("target_percent", *target_percent)?;
+            for (field, bound) in [("min_percent", min_percent), ("max_percent", max_percent)] {
+                if let Some(bound) = bound {
+                    validate_percent(field, *bound)?;
+                }
+            }
+            if min_percent.is_some_and(|min| min > *target_percent) || max_percent.is_some_and(|max| max < *target_percent) {
+                return Err(invalid("target_percent must lie between min_percent and max_percent"));
+            }
+            Ok(())
+        }
+        Strategy::DirectMarketAccess { .. } | Strategy::Other { .. } => Ok(()),
+    }
+}
+
+/// Checks the fields each order type requires, before the order is sent.
+///
+/// Not called by `create_order`; combinations the API may accept are left to the API.
+pub fn validate_order(params: &CreateOrderParams) -> Result<(), Error> {
+    if params.symbol.trim().is_empty() {
+        return Err(invalid("symbol is required"));
+    }
+    parse_positive("quantity", &params.quantity)?;
+
+    match &params.price {
+        Some(price) => {
+            parse_positive("price", price)?;
+        }
+        None if params.order_type.requires_price() => {
+            return Err(invalid(&format!("{} orders require a price", params.order_type)));
+        }
+        None => {}
+    }
+
+    match &params.stop_price {
+        Some(stop_price) => {
+            parse_positive("stop_price", stop_price)?;
+        }
+        None if params.order_type.requires_stop_price() => {
+            return Err(invalid(&format!("{} orders require a stop_price", params.order_type)));
+        }
+        None => {}
+    }
+
+    validate_strategy(&params.strategy)
+}
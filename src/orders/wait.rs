@@ -0,0 +1,184 @@
+use crate::client::AsyncClearstreetClient;
+use crate::error::{Error, ErrorType};
+use crate::orders::{Order, OrderState};
+use crate::trades::{list_trades_since, Trade};
+use crate::websockets::broadcast::{ActivityBroadcaster, ActivityFilter, ActivitySubscription};
+use crate::websockets::payloads::ActivityMessage;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const INITIAL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The order once the awaited status was reached, with every fill against it.
+#[derive(Debug, Clone)]
+pub struct OrderCompletion {
+    pub order: Order,
+    pub fills: Vec<Trade>,
+}
+
+fn timed_out(order_id: &str) -> Error {
+    let msg = format!("Timed out waiting for order {}", order_id);
+    Error::new(ErrorType::TimeoutError, &msg)
+}
+
+// A closed order that does not satisfy the predicate never will.
+fn check_closed(order: &Order) -> Result<(), Error> {
+    match order.state {
+        OrderState::Open | OrderState::Unknown(_) => Ok(()),
+        OrderState::Rejected => {
+            let msg = format!("Order {} was rejected: {}", order.order_id, order.text);
+            Err(Error::new(ErrorType::OrderRejected, &msg))
+        }
+        OrderState::Closed => {
+            let msg = format!(
+                "Order {} closed with status {:?} before reaching the awaited status",
+                order.order_id, order.status
+            );
+            Err(Error::new(ErrorType::OrderClosed, &msg))
+        }
+    }
+}
+
+fn filled_quantity(order: &Order) -> f64 {
+    order.filled_quantity.trim().parse().unwrap_or_default()
+}
+
+fn fill_quantity(fills: &[Trade]) -> f64 {
+    fills.iter().filter_map(|t| t.quantity.trim().parse::<f64>().ok()).sum()
+}
+
+async fn fetch_fills(client: &dyn AsyncClearstreetClient, order: &Order) -> Result<Vec<Trade>, Error> {
+    let trades = list_trades_since(client, order.created_at).await?;
+    Ok(trades.into_iter().filter(|t| t.order_id == order.order_id).collect())
+}
+
+async fn complete(
+    client: &dyn AsyncClearstreetClient,
+    order: Order,
+    fills: Vec<Trade>,
+) -> Result<OrderCompletion, Error> {
+    // Fills that happened before the feed was subscribed are only available over REST.
+    let fills = if fill_quantity(&fills) < filled_quantity(&order) {
+        fetch_fills(client, &order).await?
+    } else {
+        fills
+    };
+
+    Ok(OrderCompletion { order, fills })
+}
+
+async fn poll<F>(
+    client: &dyn AsyncClearstreetClient,
+    order_id: &str,
+    predicate: &F,
+    deadline: Instant,
+) -> Result<Order, Error>
+where
+    F: Fn(&Order) -> bool,
+{
+    let mut interval = INITIAL_POLL_INTERVAL;
+
+    loop {
+        let order = client.get_order(order_id).await?;
+        if predicate(&order) {
+            return Ok(order);
+        }
+        check_closed(&order)?;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(timed_out(order_id));
+        }
+
+        tokio::time::sleep(interval.min(deadline - now)).await;
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// Follows the order through the feed. `None` means the feed stopped and polling takes over.
+async fn follow_feed<F>(
+    subscription: &mut ActivitySubscription,
+    mut order: Order,
+    fills: &mut Vec<Trade>,
+    predicate: &F,
+    deadline: Instant,
+) -> Result<Option<Order>, Error>
+where
+    F: Fn(&Order) -> bool,
+{
+    loop {
+        if predicate(&order) {
+            return Ok(Some(order));
+        }
+        check_closed(&order)?;
+
+        let message = match tokio::time::timeout_at(deadline, subscription.recv()).await {
+            Ok(Some(Ok(message))) => message,
+            Ok(Some(Err(e))) => {
+                tracing::warn!("Activity feed error while waiting for order {}: {}", order.order_id, e);
+                return Ok(None);
+            }
+            Ok(None) => return Ok(None),
+            Err(_) => return Err(timed_out(&order.order_id)),
+        };
+
+        match message {
+            ActivityMessage::OrderUpdate(update) if update.payload.data.version > order.version => {
+                order = update.payload.data;
+            }
+            ActivityMessage::TradeNotice(notice) => {
+                let trade = notice.payload.data;
+                if !fills.iter().any(|t| t.trade_id == trade.trade_id) {
+                    fills.push(trade);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Waits until `predicate` holds for the order, or fails once it closes without doing so.
+///
+/// With a connected `feed` the order is followed through the activity feed, otherwise
+/// `get_order` is polled with exponential backoff. Polling also takes over if the feed
+/// drops while waiting.
+pub async fn wait_for_status<F>(
+    client: &dyn AsyncClearstreetClient,
+    feed: Option<&ActivityBroadcaster>,
+    order_id: &str,
+    predicate: F,
+    timeout: Duration,
+) -> Result<OrderCompletion, Error>
+where
+    F: Fn(&Order) -> bool,
+{
+    let deadline = Instant::now() + timeout;
+    let mut fills = Vec::new();
+
+    if let Some(feed) = feed.filter(|feed| feed.is_connected()) {
+        // Subscribe before fetching so no update between the two is missed.
+        let mut subscription = feed.subscribe(ActivityFilter::all().order_id(order_id));
+        let order = client.get_order(order_id).await?;
+
+        match follow_feed(&mut subscription, order, &mut fills, &predicate, deadline).await? {
+            Some(order) => return complete(client, order, fills).await,
+            None => {
+                tracing::debug!("Activity feed unavailable, polling order {}", order_id);
+            }
+        }
+    }
+
+    let order = poll(client, order_id, &predicate, deadline).await?;
+    complete(client, order, fills).await
+}
+
+/// Waits until the order is no longer open, whatever the outcome.
+pub async fn wait_for_terminal(
+    client: &dyn AsyncClearstreetClient,
+    feed: Option<&ActivityBroadcaster>,
+    order_id: &str,
+    timeout: Duration,
+) -> Result<OrderCompletion, Error> {
+    wait_for_status(client, feed, order_id, Order::is_terminal, timeout).await
+}
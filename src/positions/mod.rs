@@ -1,10 +1,17 @@
-use crate::error::Error;
+pub mod option_events;
+pub mod tracker;
+
+use crate::error::{Error, ErrorType};
+use crate::instruments::Instrument;
+use crate::instruments::option_symbol::{parse_option_symbol, OptionSymbol};
 use crate::utils::{parse_response};
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature="async")]
 use crate::client::async_client::AsyncClient;
+#[cfg(feature="async")]
+use crate::client::AsyncClearstreetClient;
 #[cfg(feature="sync")]
 use crate::client::sync_client::SyncClient;
 #[cfg(feature="sync")]
@@ -19,12 +26,44 @@ pub struct Position {
     pub average_cost: f64,
 }
 
+impl Position {
+    /// The option contract held, if `symbol` is an option symbol.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        parse_option_symbol(&self.symbol)
+    }
+
+    pub fn is_option(&self) -> bool {
+        self.option_symbol().is_some()
+    }
+
+    /// Signed quantity, in contracts for options and shares otherwise.
+    pub fn quantity_f64(&self) -> Result<f64, Error> {
+        self.quantity.trim().parse::<f64>().map_err(|e| {
+            let msg = format!("Invalid quantity '{}' for {}: {}", self.quantity, self.symbol, e);
+            Error::new(ErrorType::ParseError, &msg)
+        })
+    }
+
+    /// Signed exposure in units of the underlying, using the multiplier of `instrument`.
+    pub fn underlying_quantity(&self, instrument: &Instrument) -> Result<f64, Error> {
+        Ok(self.quantity_f64()? * instrument.contract_multiplier())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ListPositionsResponse {
     pub data: Vec<Position>,
     pub next_page_token: Option<String>,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ListPositionsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_token: Option<String>,
+}
+
 #[cfg(feature = "async")]
 pub async fn get_position(client: &AsyncClient, symbol: &str) -> Result<Position, Error> {
     let api_url: &str = &client.client_options.api_url;
@@ -51,6 +90,39 @@ pub async fn list_positions(client: &AsyncClient) -> Result<ListPositionsRespons
     parse_response::<ListPositionsResponse>(response).await
 }
 
+#[cfg(feature = "async")]
+pub async fn list_positions_page(
+    client: &AsyncClient,
+    params: ListPositionsParams,
+) -> Result<ListPositionsResponse, Error> {
+    let api_url: &str = &client.client_options.api_url;
+    let account_id: &str = &client.client_options.account_id;
+
+    let url = format!("{api_url}/studio/v2/accounts/{account_id}/positions");
+
+    let request_builder = client.client.get(&url).query(&params);
+    let response: Response = request_builder.send().await?;
+
+    parse_response::<ListPositionsResponse>(response).await
+}
+
+/// Every position, following `next_page_token` until the last page.
+#[cfg(feature = "async")]
+pub async fn list_all_positions(client: &dyn AsyncClearstreetClient) -> Result<Vec<Position>, Error> {
+    let mut positions = Vec::new();
+    let mut params = ListPositionsParams::default();
+
+    loop {
+        let page = client.list_positions_page(params.clone()).await?;
+        positions.extend(page.data);
+
+        match page.next_page_token.filter(|token| !token.is_empty()) {
+            Some(token) => params.page_token = Some(token),
+            None => return Ok(positions),
+        }
+    }
+}
+
 #[cfg(feature = "sync")]
 pub fn get_position_blocking(client: &SyncClient, symbol: &str) -> Result<Position, Error> {
     let url = format!(
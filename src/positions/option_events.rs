@@ -0,0 +1,184 @@
+use crate::error::{Error, ErrorType};
+use crate::instruments::option_symbol::{parse_option_symbol, OptionSymbol, OptionType};
+use crate::orders::OrderSide;
+use crate::positions::tracker::{PositionChange, PositionSource};
+use crate::trades::Trade;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+// Quantities closer than this are treated as equal.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionEventKind {
+    /// A long position was exercised into the underlying.
+    Exercise,
+    /// A short position was assigned.
+    Assignment,
+    /// The contract expired without delivering the underlying.
+    Expiration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionEvent {
+    pub symbol: String,
+    pub contract: OptionSymbol,
+    pub kind: OptionEventKind,
+    /// Contracts removed from the position, always positive.
+    pub contracts: f64,
+    /// Signed change in the underlying position, zero for an expiration.
+    pub underlying_quantity: f64,
+}
+
+#[derive(Debug, Clone)]
+struct PendingOption {
+    symbol: String,
+    contract: OptionSymbol,
+    kind: OptionEventKind,
+    contracts: f64,
+    underlying_quantity: f64,
+}
+
+impl PendingOption {
+    fn into_event(self, kind: OptionEventKind, underlying_quantity: f64) -> OptionEvent {
+        OptionEvent {
+            symbol: self.symbol,
+            contract: self.contract,
+            kind,
+            contracts: self.contracts,
+            underlying_quantity,
+        }
+    }
+}
+
+/// Recognises exercise, assignment and expiration from position changes.
+///
+/// The activity feed has no message for these; they arrive as position updates with
+/// no fills behind them. An option position shrinking towards zero is matched with
+/// an underlying change of `contracts * multiplier` in the delivered direction, in
+/// either order, and reported as an exercise when the position was long or an
+/// assignment when short. Reductions still unmatched on or after expiry are reported
+/// as expirations by [`OptionEventDetector::expire`].
+///
+/// Feed it every trade and every [`PositionChange`] of the account, so closing fills
+/// are not mistaken for either. Option multipliers come from the change, see
+/// [`PositionTracker::set_instrument`](crate::positions::tracker::PositionTracker::set_instrument).
+#[derive(Debug, Default)]
+pub struct OptionEventDetector {
+    traded: HashMap<String, f64>,
+    pending_options: Vec<PendingOption>,
+    pending_underlying: HashMap<String, f64>,
+}
+
+impl OptionEventDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a fill so the position change it causes is not reported.
+    pub fn apply_trade(&mut self, trade: &Trade) -> Result<(), Error> {
+        let quantity = trade.quantity.trim().parse::<f64>().map_err(|e| {
+            let msg = format!("Invalid quantity '{}' for trade {}: {}", trade.quantity, trade.trade_id, e);
+            Error::new(ErrorType::ParseError, &msg)
+        })?;
+
+        let signed = match trade.side {
+            OrderSide::Buy => quantity,
+            _ => -quantity,
+        };
+        *self.traded.entry(trade.symbol.clone()).or_default() += signed;
+        Ok(())
+    }
+
+    /// Checks a position change, returning any events it completes.
+    pub fn apply_change(&mut self, change: &PositionChange) -> Vec<OptionEvent> {
+        let symbol = &change.current.symbol;
+        let previous = change.previous.as_ref().map(|p| p.quantity).unwrap_or_default();
+        let delta = change.current.quantity - previous;
+
+        let traded = self.traded.entry(symbol.clone()).or_default();
+        if change.current.source == PositionSource::Trade {
+            // Already derived from a fill, which the trade accounted for.
+            *traded -= delta;
+            return Vec::new();
+        }
+        let unexplained = delta - std::mem::take(traded);
+        if unexplained.abs() < EPSILON {
+            return Vec::new();
+        }
+
+        let Some(contract) = parse_option_symbol(symbol) else {
+            *self.pending_underlying.entry(symbol.clone()).or_default() += unexplained;
+            return self.match_underlying(symbol);
+        };
+
+        // Only a move towards flat without a fill can be an exercise or assignment.
+        if previous.abs() < EPSILON
+            || unexplained.signum() == previous.signum()
+            || unexplained.abs() > previous.abs() + EPSILON
+        {
+            return Vec::new();
+        }
+
+        let long = previous > 0.0;
+        let delivered = match (contract.option_type(), long) {
+            (OptionType::Call, true) | (OptionType::Put, false) => 1.0,
+            (OptionType::Call, false) | (OptionType::Put, true) => -1.0,
+        };
+        let root = contract.root().to_string();
+        self.pending_options.push(PendingOption {
+            symbol: symbol.clone(),
+            contract,
+            kind: if long { OptionEventKind::Exercise } else { OptionEventKind::Assignment },
+            contracts: unexplained.abs(),
+            underlying_quantity: delivered * unexplained.abs() * change.current.multiplier,
+        });
+        self.match_underlying(&root)
+    }
+
+    /// Reports option reductions still unmatched for contracts expiring on or before
+    /// `date` as expirations, and forgets unmatched underlying changes. Call once the
+    /// session's updates have been applied.
+    pub fn expire(&mut self, date: NaiveDate) -> Vec<OptionEvent> {
+        let (expired, pending): (Vec<_>, Vec<_>) =
+            self.pending_options.drain(..).partition(|option| option.contract.expiry() <= date);
+        self.pending_options = pending;
+        self.pending_underlying.clear();
+
+        expired
+            .into_iter()
+            .map(|option| option.into_event(OptionEventKind::Expiration, 0.0))
+            .collect()
+    }
+
+    fn match_underlying(&mut self, root: &str) -> Vec<OptionEvent> {
+        let mut events = Vec::new();
+        let mut index = 0;
+
+        while index < self.pending_options.len() {
+            let option = &self.pending_options[index];
+            let available = self.pending_underlying.get(root).copied().unwrap_or_default();
+            let covered = option.contract.root() == root
+                && available.signum() == option.underlying_quantity.signum()
+                && available.abs() + EPSILON >= option.underlying_quantity.abs();
+
+            if !covered {
+                index += 1;
+                continue;
+            }
+
+            let option = self.pending_options.remove(index);
+            let remaining = available - option.underlying_quantity;
+            if remaining.abs() < EPSILON {
+                self.pending_underlying.remove(root);
+            } else {
+                self.pending_underlying.insert(root.to_string(), remaining);
+            }
+
+            let (kind, underlying_quantity) = (option.kind, option.underlying_quantity);
+            events.push(option.into_event(kind, underlying_quantity));
+        }
+
+        events
+    }
+}
@@ -0,0 +1,277 @@
+use crate::client::AsyncClearstreetClient;
+use crate::error::{Error, ErrorType};
+use crate::instruments::option_symbol::parse_option_symbol;
+use crate::instruments::{Instrument, STANDARD_CONTRACT_MULTIPLIER};
+use crate::orders::OrderSide;
+use crate::positions::{list_all_positions, Position};
+use crate::trades::Trade;
+use crate::websockets::payloads::ActivityMessage;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+fn parse_quantity(field: &str, value: &str) -> Result<f64, Error> {
+    value.trim().parse::<f64>().map_err(|e| {
+        let msg = format!("Invalid {} '{}': {}", field, value, e);
+        Error::new(ErrorType::ParseError, &msg)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionSource {
+    Snapshot,
+    PositionUpdate,
+    /// Derived from a `TradeNotice` and replaced by the next `PositionUpdate`.
+    Trade,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionState {
+    pub symbol: String,
+    /// Signed quantity, in contracts for options, negative when short.
+    pub quantity: f64,
+    pub average_cost: f64,
+    /// Units of the underlying per unit of quantity, from the instrument when set.
+    pub multiplier: f64,
+    pub source: PositionSource,
+}
+
+impl PositionState {
+    /// Signed cost basis, `quantity * average_cost * multiplier`.
+    pub fn exposure(&self) -> f64 {
+        self.quantity * self.average_cost * self.multiplier
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.quantity == 0.0
+    }
+}
+
+/// Cost-basis exposure across every tracked position. `short` is reported as a positive amount.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Exposure {
+    pub long: f64,
+    pub short: f64,
+    pub gross: f64,
+    pub net: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionChange {
+    pub previous: Option<PositionState>,
+    pub current: PositionState,
+}
+
+/// Local view of the account's positions, kept current from activity messages.
+///
+/// `PositionUpdate`s are authoritative. When trade derivation is enabled, each
+/// `TradeNotice` sets the quantity from its `running_position` in the meantime, with
+/// the average cost estimated from the fill price.
+///
+/// Exposure uses the multiplier from [`PositionTracker::set_instrument`] when the
+/// instrument is known. Otherwise option symbols use the standard contract multiplier
+/// and every other symbol uses 1.
+pub struct PositionTracker {
+    positions: HashMap<String, PositionState>,
+    multipliers: HashMap<String, f64>,
+    derive_from_trades: bool,
+    changes: broadcast::Sender<PositionChange>,
+}
+
+impl Default for PositionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(1024);
+
+        Self {
+            positions: HashMap::new(),
+            multipliers: HashMap::new(),
+            derive_from_trades: false,
+            changes,
+        }
+    }
+
+    pub fn derive_from_trades(mut self, enabled: bool) -> Self {
+        self.derive_from_trades = enabled;
+        self
+    }
+
+    /// Uses the contract multiplier of `instrument` for each of its symbols.
+    pub fn set_instrument(&mut self, instrument: &Instrument) {
+        let multiplier = instrument.contract_multiplier();
+        for detail in &instrument.symbols {
+            self.multipliers.insert(detail.symbol.clone(), multiplier);
+            if let Some(position) = self.positions.get_mut(&detail.symbol) {
+                position.multiplier = multiplier;
+            }
+        }
+    }
+
+    fn multiplier(&self, symbol: &str) -> f64 {
+        self.multipliers.get(symbol).copied().unwrap_or_else(|| {
+            if parse_option_symbol(symbol).is_some() {
+                STANDARD_CONTRACT_MULTIPLIER
+            } else {
+                1.0
+            }
+        })
+    }
+
+    /// Replaces the tracked positions with every page of `list_positions`.
+    ///
+    /// Symbols held before but missing from the new snapshot are removed and published
+    /// as a change to a flat position.
+    pub async fn seed(&mut self, client: &dyn AsyncClearstreetClient) -> Result<usize, Error> {
+        let snapshot = list_all_positions(client).await?;
+
+        let mut positions = HashMap::new();
+        for position in &snapshot {
+            let state = PositionState {
+                symbol: position.symbol.clone(),
+                quantity: parse_quantity("quantity", &position.quantity)?,
+                average_cost: position.average_cost,
+                multiplier: self.multiplier(&position.symbol),
+                source: PositionSource::Snapshot,
+            };
+            positions.insert(state.symbol.clone(), state);
+        }
+
+        let loaded = positions.len();
+        let mut previous = std::mem::replace(&mut self.positions, positions);
+        for current in self.positions.values() {
+            let _ = self.changes.send(PositionChange {
+                previous: previous.remove(&current.symbol),
+                current: current.clone(),
+            });
+        }
+        for (symbol, removed) in previous {
+            let _ = self.changes.send(PositionChange {
+                current: PositionState {
+                    symbol,
+                    quantity: 0.0,
+                    average_cost: 0.0,
+                    multiplier: removed.multiplier,
+                    source: PositionSource::Snapshot,
+                },
+                previous: Some(removed),
+            });
+        }
+
+        tracing::debug!("Position tracker seeded with {} positions", loaded);
+        Ok(loaded)
+    }
+
+    fn set(&mut self, current: PositionState) -> Option<PositionChange> {
+        let previous = self.positions.insert(current.symbol.clone(), current.clone());
+        if previous.as_ref().is_some_and(|p| p.quantity == current.quantity && p.average_cost == current.average_cost) {
+            return None;
+        }
+
+        let change = PositionChange { previous, current };
+        // No receivers is fine, notifications are optional.
+        let _ = self.changes.send(change.clone());
+        Some(change)
+    }
+
+    pub fn apply_position(&mut self, position: &Position) -> Result<Option<PositionChange>, Error> {
+        let quantity = parse_quantity("quantity", &position.quantity)?;
+
+        Ok(self.set(PositionState {
+            symbol: position.symbol.clone(),
+            quantity,
+            average_cost: position.average_cost,
+            multiplier: self.multiplier(&position.symbol),
+            source: PositionSource::PositionUpdate,
+        }))
+    }
+
+    /// Sets the interim position from a fill. Does nothing unless trade derivation is enabled.
+    pub fn apply_trade(&mut self, trade: &Trade) -> Result<Option<PositionChange>, Error> {
+        if !self.derive_from_trades {
+            return Ok(None);
+        }
+
+        let running_position = parse_quantity("running_position", &trade.running_position)?;
+        let price = parse_quantity("price", &trade.price)?;
+        let fill = parse_quantity("quantity", &trade.quantity)?;
+
+        let (held, held_cost) = self
+            .positions
+            .get(&trade.symbol)
+            .map(|p| (p.quantity, p.average_cost))
+            .unwrap_or_default();
+
+        let signed_fill = match trade.side {
+            OrderSide::Buy => fill,
+            _ => -fill,
+        };
+
+        let average_cost = if running_position == 0.0 {
+            0.0
+        } else if held == 0.0 || held.signum() != running_position.signum() {
+            // Opened or flipped, the remainder was bought at the fill price.
+            price
+        } else if running_position.abs() > held.abs() {
+            (held.abs() * held_cost + signed_fill.abs() * price) / running_position.abs()
+        } else {
+            held_cost
+        };
+
+        Ok(self.set(PositionState {
+            symbol: trade.symbol.clone(),
+            quantity: running_position,
+            average_cost,
+            multiplier: self.multiplier(&trade.symbol),
+            source: PositionSource::Trade,
+        }))
+    }
+
+    /// Applies a `PositionUpdate` or `TradeNotice`; other messages are ignored.
+    pub fn apply_message(&mut self, message: &ActivityMessage) -> Result<Option<PositionChange>, Error> {
+        match message {
+            ActivityMessage::PositionUpdate(update) => self.apply_position(&update.payload.data),
+            ActivityMessage::TradeNotice(notice) => self.apply_trade(&notice.payload.data),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PositionChange> {
+        self.changes.subscribe()
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&PositionState> {
+        self.positions.get(symbol)
+    }
+
+    /// Signed quantity held in `symbol`, zero if untracked.
+    pub fn quantity(&self, symbol: &str) -> f64 {
+        self.positions.get(symbol).map(|p| p.quantity).unwrap_or_default()
+    }
+
+    pub fn average_cost(&self, symbol: &str) -> Option<f64> {
+        self.positions.get(symbol).map(|p| p.average_cost)
+    }
+
+    /// Every non-flat position.
+    pub fn positions(&self) -> Vec<&PositionState> {
+        self.positions.values().filter(|p| !p.is_flat()).collect()
+    }
+
+    pub fn exposure(&self) -> Exposure {
+        self.positions.values().fold(Exposure::default(), |mut total, position| {
+            let exposure = position.exposure();
+            if exposure > 0.0 {
+                total.long += exposure;
+            } else {
+                total.short -= exposure;
+            }
+            total.gross = total.long + total.short;
+            total.net = total.long - total.short;
+            total
+        })
+    }
+}
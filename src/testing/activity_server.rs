@@ -0,0 +1,331 @@
+use crate::client::ClientOptions;
+use crate::error::Error;
+use crate::websockets::payloads::{
+    parse_message, ActivityMessage, Heartbeat, HeartbeatPayload, PayloadType, ReplayComplete,
+    ReplayCompletePayload, SubscribeActivity, SubscribeActivityAck, SubscribeActivityAckPayload,
+};
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{accept_async, WebSocketStream};
+
+#[derive(Debug, Clone)]
+pub struct ActivityServerOptions {
+    /// Token every `SubscribeActivity` message must carry, until [`ActivityServer::set_token`].
+    pub token: String,
+    /// Accounts that may be subscribed. Empty accepts any account.
+    pub accounts: Vec<String>,
+    /// Frames sent to each new session between the ack and `ReplayComplete`.
+    pub replay: Vec<String>,
+    pub heartbeat_interval: Option<Duration>,
+}
+
+impl Default for ActivityServerOptions {
+    fn default() -> Self {
+        Self {
+            token: "test-token".to_string(),
+            accounts: Vec::new(),
+            replay: Vec::new(),
+            heartbeat_interval: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ServerCommand {
+    Push(String),
+    Disconnect,
+}
+
+#[derive(Default)]
+struct ServerState {
+    connections: AtomicUsize,
+    token: Mutex<String>,
+    subscriptions: Mutex<Vec<SubscribeActivity>>,
+}
+
+/// Local websocket server speaking the Studio activity protocol, for tests.
+///
+/// Each session must open with a `SubscribeActivity` message carrying the configured
+/// token. The server then acks, sends the configured replay frames and `ReplayComplete`,
+/// and afterwards relays whatever the test pushes. Further subscribe messages on the
+/// same connection add accounts whose frames are relayed.
+pub struct ActivityServer {
+    address: SocketAddr,
+    commands: broadcast::Sender<ServerCommand>,
+    state: Arc<ServerState>,
+    task: JoinHandle<()>,
+}
+
+impl ActivityServer {
+    pub async fn start(options: ActivityServerOptions) -> Result<Self, Error> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let (commands, _) = broadcast::channel(1024);
+        let state = Arc::new(ServerState {
+            token: Mutex::new(options.token.clone()),
+            ..Default::default()
+        });
+
+        let task = {
+            let commands = commands.clone();
+            let state = state.clone();
+
+            tokio::spawn(async move {
+                while let Ok((tcp_stream, peer)) = listener.accept().await {
+                    tracing::debug!("Activity server accepted connection from {}", peer);
+                    // Subscribe before the handshake so nothing pushed after a client connects is lost.
+                    let session_commands = commands.subscribe();
+                    tokio::spawn(run_session(tcp_stream, options.clone(), session_commands, state.clone()));
+                }
+            })
+        };
+
+        Ok(Self {
+            address,
+            commands,
+            state,
+            task,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.address)
+    }
+
+    /// Client options pointing both the REST and websocket URLs at this server.
+    pub fn client_options(&self, account_id: &str) -> ClientOptions {
+        ClientOptions {
+            api_url: format!("http://{}", self.address),
+            websocket_url: self.url(),
+            account_id: account_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn token(&self) -> String {
+        self.state.token.lock().unwrap().clone()
+    }
+
+    /// Replaces the accepted token, as if the old one expired. Open sessions stay up.
+    pub fn set_token(&self, token: &str) {
+        *self.state.token.lock().unwrap() = token.to_string();
+    }
+
+    /// Sends `message` to every connected session.
+    pub fn push(&self, message: &ActivityMessage) -> Result<(), Error> {
+        self.push_raw(message.to_json()?);
+        Ok(())
+    }
+
+    /// Sends a raw text frame to every connected session.
+    pub fn push_raw(&self, frame: impl Into<String>) {
+        let _ = self.commands.send(ServerCommand::Push(frame.into()));
+    }
+
+    /// Drops every open connection without a close handshake.
+    pub fn disconnect_all(&self) {
+        let _ = self.commands.send(ServerCommand::Disconnect);
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.state.connections.load(Ordering::SeqCst)
+    }
+
+    /// Every accepted `SubscribeActivity` message, in arrival order.
+    pub fn subscriptions(&self) -> Vec<SubscribeActivity> {
+        self.state.subscriptions.lock().unwrap().clone()
+    }
+
+    /// Waits until at least `count` subscriptions have been accepted.
+    pub async fn wait_for_subscriptions(&self, count: usize, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        while tokio::time::Instant::now() < deadline {
+            if self.state.subscriptions.lock().unwrap().len() >= count {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        false
+    }
+}
+
+impl Drop for ActivityServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.disconnect_all();
+    }
+}
+
+fn ack_frame(success: bool, details: &str) -> Result<String, Error> {
+    ActivityMessage::SubscribeActivityAck(SubscribeActivityAck {
+        timestamp: Utc::now().timestamp_millis(),
+        payload: SubscribeActivityAckPayload {
+            payload_type: PayloadType::SubscribeActivityAck,
+            success,
+            details: details.to_string(),
+        },
+    })
+    .to_json()
+}
+
+fn replay_complete_frame() -> Result<String, Error> {
+    ActivityMessage::ReplayComplete(ReplayComplete {
+        timestamp: Utc::now().timestamp_millis(),
+        payload: ReplayCompletePayload {
+            payload_type: PayloadType::ReplayComplete,
+        },
+    })
+    .to_json()
+}
+
+fn heartbeat_frame() -> Result<String, Error> {
+    ActivityMessage::Heartbeat(Heartbeat {
+        timestamp: Utc::now().timestamp_millis(),
+        payload: HeartbeatPayload {
+            payload_type: PayloadType::Heartbeat,
+        },
+    })
+    .to_json()
+}
+
+fn validate_subscription(
+    text: &str,
+    options: &ActivityServerOptions,
+    state: &ServerState,
+) -> Result<SubscribeActivity, String> {
+    let subscription: SubscribeActivity =
+        serde_json::from_str(text).map_err(|e| format!("invalid subscribe message: {}", e))?;
+
+    if subscription.payload.payload_type != PayloadType::SubscribeActivity {
+        return Err("expected subscribe-activity".to_string());
+    }
+    if subscription.authorization != *state.token.lock().unwrap() {
+        return Err("invalid authorization".to_string());
+    }
+    if !options.accounts.is_empty() && !options.accounts.contains(&subscription.payload.account_id) {
+        return Err(format!("unknown account: {}", subscription.payload.account_id));
+    }
+
+    Ok(subscription)
+}
+
+async fn send_text(ws_stream: &mut WebSocketStream<TcpStream>, frame: String) -> Result<(), Error> {
+    ws_stream.send(Message::Text(frame.into())).await?;
+    Ok(())
+}
+
+async fn run_session(
+    tcp_stream: TcpStream,
+    options: ActivityServerOptions,
+    commands: broadcast::Receiver<ServerCommand>,
+    state: Arc<ServerState>,
+) {
+    let mut ws_stream = match accept_async(tcp_stream).await {
+        Ok(ws_stream) => ws_stream,
+        Err(e) => {
+            tracing::warn!("Activity server handshake failed: {}", e);
+            return;
+        }
+    };
+
+    state.connections.fetch_add(1, Ordering::SeqCst);
+    if let Err(e) = serve_session(&mut ws_stream, &options, commands, &state).await {
+        tracing::debug!("Activity server session ended: {}", e);
+    }
+    state.connections.fetch_sub(1, Ordering::SeqCst);
+}
+
+async fn subscribe(
+    ws_stream: &mut WebSocketStream<TcpStream>,
+    options: &ActivityServerOptions,
+    state: &ServerState,
+    subscription: SubscribeActivity,
+) -> Result<(), Error> {
+    state.subscriptions.lock().unwrap().push(subscription);
+    send_text(ws_stream, ack_frame(true, "subscribed")?).await?;
+
+    for frame in &options.replay {
+        send_text(ws_stream, frame.clone()).await?;
+    }
+    send_text(ws_stream, replay_complete_frame()?).await
+}
+
+// Frames for accounts this session has not subscribed to are not delivered.
+fn is_subscribed(frame: &str, accounts: &HashSet<String>) -> bool {
+    match parse_message(frame.to_string().into()) {
+        Ok(message) => message.account_id().is_none_or(|account_id| accounts.contains(account_id)),
+        Err(_) => true,
+    }
+}
+
+async fn serve_session(
+    ws_stream: &mut WebSocketStream<TcpStream>,
+    options: &ActivityServerOptions,
+    mut commands: broadcast::Receiver<ServerCommand>,
+    state: &ServerState,
+) -> Result<(), Error> {
+    let subscription = loop {
+        match ws_stream.next().await {
+            Some(Ok(Message::Text(text))) => break validate_subscription(text.as_str(), options, state),
+            Some(Ok(Message::Close(_))) | None => return Ok(()),
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+        }
+    };
+
+    let mut accounts = HashSet::new();
+
+    match subscription {
+        Ok(subscription) => {
+            accounts.insert(subscription.payload.account_id.clone());
+            subscribe(ws_stream, options, state, subscription).await?;
+        }
+        Err(details) => {
+            send_text(ws_stream, ack_frame(false, &details)?).await?;
+            ws_stream.close(None).await?;
+            return Ok(());
+        }
+    }
+
+    let mut heartbeat = options.heartbeat_interval.map(tokio::time::interval);
+
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Ok(ServerCommand::Push(frame)) => {
+                    if is_subscribed(&frame, &accounts) {
+                        send_text(ws_stream, frame).await?;
+                    }
+                }
+                Ok(ServerCommand::Disconnect) | Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            },
+            _ = async { heartbeat.as_mut().unwrap().tick().await }, if heartbeat.is_some() => {
+                send_text(ws_stream, heartbeat_frame()?).await?;
+            }
+            incoming = ws_stream.next() => match incoming {
+                Some(Ok(Message::Text(text))) => match validate_subscription(text.as_str(), options, state) {
+                    Ok(subscription) => {
+                        accounts.insert(subscription.payload.account_id.clone());
+                        subscribe(ws_stream, options, state, subscription).await?;
+                    }
+                    Err(details) => send_text(ws_stream, ack_frame(false, &details)?).await?,
+                },
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+            },
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod activity_server;
+
+pub use crate::testing::activity_server::{ActivityServer, ActivityServerOptions};
@@ -1,7 +1,9 @@
 use reqwest::{RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
 use crate::client::async_client::AsyncClient;
+use crate::client::AsyncClearstreetClient;
 use crate::error::Error;
+use crate::instruments::option_symbol::{parse_option_symbol, OptionSymbol};
 use crate::orders::OrderSide;
 use crate::utils::parse_response;
 
@@ -19,12 +21,27 @@ pub struct Trade {
     pub running_position: String,
 }
 
+impl Trade {
+    /// The option contract traded, if `symbol` is an option symbol.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        parse_option_symbol(&self.symbol)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ListTradesResponse {
     pub data: Vec<Trade>,
     pub next_page_token: Option<String>,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ListTradesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_token: Option<String>,
+}
+
 #[cfg(feature = "async")]
 pub async fn get_trade(client: &AsyncClient, trade_id: &str) -> Result<Trade, Error> {
     let api_url: &str = &client.client_options.api_url;
@@ -50,3 +67,57 @@ pub async fn list_trades(client: &AsyncClient) -> Result<ListTradesResponse, Err
 
     parse_response::<ListTradesResponse>(response).await
 }
+
+#[cfg(feature = "async")]
+pub async fn list_trades_page(client: &AsyncClient, params: ListTradesParams) -> Result<ListTradesResponse, Error> {
+    let api_url: &str = &client.client_options.api_url;
+    let account_id: &str = &client.client_options.account_id;
+
+    let url: String = format!("{api_url}/studio/v2/accounts/{account_id}/trades");
+
+    let request_builder: RequestBuilder = client.client.get(&url).query(&params);
+    let response: Response = request_builder.send().await?;
+
+    parse_response::<ListTradesResponse>(response).await
+}
+
+/// Every trade, following `next_page_token` until the last page.
+#[cfg(feature = "async")]
+pub async fn list_all_trades(client: &dyn AsyncClearstreetClient) -> Result<Vec<Trade>, Error> {
+    let mut trades = Vec::new();
+    let mut params = ListTradesParams::default();
+
+    loop {
+        let page = client.list_trades_page(params.clone()).await?;
+        trades.extend(page.data);
+
+        match page.next_page_token.filter(|token| !token.is_empty()) {
+            Some(token) => params.page_token = Some(token),
+            None => return Ok(trades),
+        }
+    }
+}
+
+/// Trades created at or after `since` (milliseconds).
+///
+/// Paging stops at the first page listed newest first that reaches back before `since`.
+/// A listing in any other order is read to the last page.
+#[cfg(feature = "async")]
+pub async fn list_trades_since(client: &dyn AsyncClearstreetClient, since: i64) -> Result<Vec<Trade>, Error> {
+    let mut trades = Vec::new();
+    let mut params = ListTradesParams::default();
+
+    loop {
+        let page = client.list_trades_page(params.clone()).await?;
+        let reached_since = match (page.data.first(), page.data.last()) {
+            (Some(newest), Some(oldest)) => newest.created_at > oldest.created_at && oldest.created_at < since,
+            _ => false,
+        };
+        trades.extend(page.data.into_iter().filter(|trade| trade.created_at >= since));
+
+        match page.next_page_token.filter(|token| !token.is_empty()) {
+            Some(token) if !reached_since => params.page_token = Some(token),
+            _ => return Ok(trades),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::error::{Error, ErrorType};
+#[cfg(feature = "async")]
+use crate::client::async_client::AsyncClient;
+#[cfg(feature = "async")]
+use crate::websockets::stream::{connect_activity_stream, ActivitySocket};
+use crate::websockets::payloads::{
+    ActivityMessage, ErrorNotice, OrderUpdate, PayloadType, PositionUpdate, TradeNotice,
+};
+use crate::websockets::stream::ActivityStream;
+use futures_util::{Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// What a subscriber does when it falls further behind than the channel capacity.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LagPolicy {
+    /// Log the number of dropped messages and carry on with the oldest retained one.
+    #[default]
+    Skip,
+    /// Yield a `ChannelError` once, then carry on.
+    Fail,
+}
+
+#[derive(Debug, Clone)]
+pub struct BroadcastOptions {
+    /// Number of messages retained for slow subscribers.
+    pub capacity: usize,
+    pub lag_policy: LagPolicy,
+}
+
+impl Default for BroadcastOptions {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            lag_policy: LagPolicy::Skip,
+        }
+    }
+}
+
+/// Selects which activity messages a subscriber receives. An empty filter matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityFilter {
+    payload_types: Vec<PayloadType>,
+    symbols: Vec<String>,
+    order_ids: Vec<String>,
+}
+
+impl ActivityFilter {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn payload_type(mut self, payload_type: PayloadType) -> Self {
+        self.payload_types.push(payload_type);
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbols.push(symbol.to_uppercase());
+        self
+    }
+
+    pub fn order_id(mut self, order_id: &str) -> Self {
+        self.order_ids.push(order_id.to_string());
+        self
+    }
+
+    pub fn matches(&self, message: &ActivityMessage) -> bool {
+        if !self.payload_types.is_empty() && !self.payload_types.contains(&message.payload_type()) {
+            return false;
+        }
+
+        if !self.symbols.is_empty() {
+            match message.symbol() {
+                Some(symbol) if self.symbols.iter().any(|s| s.eq_ignore_ascii_case(symbol)) => {}
+                _ => return false,
+            }
+        }
+
+        if !self.order_ids.is_empty() {
+            match message.order_id() {
+                Some(order_id) if self.order_ids.iter().any(|id| id == order_id) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// Shares one activity connection between any number of subscribers.
+///
+/// A background task reads the stream and publishes every message on a broadcast
+/// channel. Messages published before a subscriber joins are not delivered to it.
+/// Subscribers end once the underlying connection closes.
+pub struct ActivityBroadcaster {
+    sender: broadcast::WeakSender<ActivityMessage>,
+    lag_policy: LagPolicy,
+    task: JoinHandle<()>,
+}
+
+#[cfg(feature = "async")]
+impl ActivityBroadcaster {
+    /// Opens a new activity connection for `client` and starts broadcasting it.
+    pub async fn connect(client: &AsyncClient, options: BroadcastOptions) -> Result<Self, Error> {
+        let stream: ActivityStream<ActivitySocket> = connect_activity_stream(client).await?;
+        Ok(Self::new(stream, options))
+    }
+}
+
+impl ActivityBroadcaster {
+    pub fn new<S>(mut stream: ActivityStream<S>, options: BroadcastOptions) -> Self
+    where
+        S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin + Send + 'static,
+    {
+        let (sender, _) = broadcast::channel(options.capacity);
+        let weak_sender = sender.downgrade();
+
+        let task = tokio::spawn(async move {
+            while let Some(message) = stream.next().await {
+                match message {
+                    Ok(message) => {
+                        // No receivers is not an error, subscribers may come and go.
+                        let _ = sender.send(message);
+                    }
+                    Err(e) if e.error_type == ErrorType::ParseError => {
+                        tracing::warn!("Dropping unparseable activity message: {}", e);
+                    }
+                    Err(e) => {
+                        tracing::error!("Activity connection failed: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            tracing::debug!("Activity broadcaster stopped");
+        });
+
+        Self {
+            sender: weak_sender,
+            lag_policy: options.lag_policy,
+            task,
+        }
+    }
+
+    pub fn subscribe(&self, filter: ActivityFilter) -> ActivitySubscription {
+        let receiver = match self.sender.upgrade() {
+            Some(sender) => sender.subscribe(),
+            None => broadcast::channel(1).1,
+        };
+
+        ActivitySubscription {
+            receiver,
+            filter,
+            lag_policy: self.lag_policy,
+        }
+    }
+
+    pub fn subscribe_order_updates(&self) -> TypedSubscription<OrderUpdate> {
+        let filter = ActivityFilter::all().payload_type(PayloadType::OrderUpdate);
+        TypedSubscription::new(self.subscribe(filter), |message| match message {
+            ActivityMessage::OrderUpdate(update) => Some(update),
+            _ => None,
+        })
+    }
+
+    pub fn subscribe_trade_notices(&self, symbol: Option<&str>) -> TypedSubscription<TradeNotice> {
+        let mut filter = ActivityFilter::all().payload_type(PayloadType::TradeNotice);
+        if let Some(symbol) = symbol {
+            filter = filter.symbol(symbol);
+        }
+        TypedSubscription::new(self.subscribe(filter), |message| match message {
+            ActivityMessage::TradeNotice(notice) => Some(notice),
+            _ => None,
+        })
+    }
+
+    pub fn subscribe_position_updates(&self, symbol: Option<&str>) -> TypedSubscription<PositionUpdate> {
+        let mut filter = ActivityFilter::all().payload_type(PayloadType::PositionUpdate);
+        if let Some(symbol) = symbol {
+            filter = filter.symbol(symbol);
+        }
+        TypedSubscription::new(self.subscribe(filter), |message| match message {
+            ActivityMessage::PositionUpdate(update) => Some(update),
+            _ => None,
+        })
+    }
+
+    /// Error notices. They do not name an order, so they cannot be filtered by one.
+    pub fn subscribe_error_notices(&self) -> TypedSubscription<ErrorNotice> {
+        let filter = ActivityFilter::all().payload_type(PayloadType::ErrorNotice);
+        TypedSubscription::new(self.subscribe(filter), |message| match message {
+            ActivityMessage::ErrorNotice(notice) => Some(notice),
+            _ => None,
+        })
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender
+            .upgrade()
+            .map(|sender| sender.receiver_count())
+            .unwrap_or(0)
+    }
+
+    /// Whether the underlying connection is still being read.
+    pub fn is_connected(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stops reading the connection. Existing subscribers drain and then end.
+    pub fn shutdown(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for ActivityBroadcaster {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+pub struct ActivitySubscription {
+    receiver: broadcast::Receiver<ActivityMessage>,
+    filter: ActivityFilter,
+    lag_policy: LagPolicy,
+}
+
+impl ActivitySubscription {
+    /// Next matching message, or `None` once the connection has closed.
+    pub async fn recv(&mut self) -> Option<Result<ActivityMessage, Error>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(message) => {
+                    if self.filter.matches(&message) {
+                        return Some(Ok(message));
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Activity subscriber lagged, {} messages dropped", skipped);
+                    if self.lag_policy == LagPolicy::Fail {
+                        return Some(Err(Error::new(
+                            ErrorType::ChannelError,
+                            &format!("Subscriber lagged behind by {} messages", skipped),
+                        )));
+                    }
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<ActivityMessage, Error>> + Send {
+        futures_util::stream::unfold(self, |mut subscription| async move {
+            subscription.recv().await.map(|item| (item, subscription))
+        })
+    }
+}
+
+/// A subscription narrowed down to a single message type.
+pub struct TypedSubscription<T> {
+    inner: ActivitySubscription,
+    project: fn(ActivityMessage) -> Option<T>,
+}
+
+impl<T> TypedSubscription<T> {
+    fn new(inner: ActivitySubscription, project: fn(ActivityMessage) -> Option<T>) -> Self {
+        Self { inner, project }
+    }
+
+    pub async fn recv(&mut self) -> Option<Result<T, Error>> {
+        loop {
+            match self.inner.recv().await? {
+                Ok(message) => {
+                    if let Some(item) = (self.project)(message) {
+                        return Some(Ok(item));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::error::{Error, ErrorType};
+use crate::websockets::payloads::{
+    parse_message, ActivityMessage, BuyingPowerUpdate, ErrorNotice, Heartbeat, LocateInventoryUpdate,
+    OrderUpdate, PositionUpdate, ReplayComplete, TradeNotice,
+};
+use crate::websockets::stream::ActivityStream;
+use futures_util::{Stream, StreamExt};
+use std::io::{Read, Write};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// Callbacks for activity feed events. Every method defaults to a no-op, so
+/// implementors only override the events they care about.
+pub trait ActivityHandler: Send {
+    fn on_order_update(&mut self, _update: &OrderUpdate) {}
+
+    fn on_trade_notice(&mut self, _notice: &TradeNotice) {}
+
+    fn on_position_update(&mut self, _update: &PositionUpdate) {}
+
+    fn on_buying_power_update(&mut self, _update: &BuyingPowerUpdate) {}
+
+    fn on_locate_update(&mut self, _update: &LocateInventoryUpdate) {}
+
+    fn on_error_notice(&mut self, _notice: &ErrorNotice) {}
+
+    fn on_heartbeat(&mut self, _heartbeat: &Heartbeat) {}
+
+    fn on_replay_complete(&mut self, _complete: &ReplayComplete) {}
+
+    /// Called once when the session ends, with the error that ended it if any.
+    fn on_disconnect(&mut self, _error: Option<&Error>) {}
+}
+
+/// Routes a single message to the matching handler method.
+pub fn dispatch<H: ActivityHandler + ?Sized>(handler: &mut H, message: &ActivityMessage) {
+    match message {
+        ActivityMessage::SubscribeActivityAck(_) => {}
+        ActivityMessage::ReplayComplete(complete) => handler.on_replay_complete(complete),
+        ActivityMessage::OrderUpdate(update) => handler.on_order_update(update),
+        ActivityMessage::TradeNotice(notice) => handler.on_trade_notice(notice),
+        ActivityMessage::PositionUpdate(update) => handler.on_position_update(update),
+        ActivityMessage::BuyingPowerUpdate(update) => handler.on_buying_power_update(update),
+        ActivityMessage::LocateInventoryUpdate(update) => handler.on_locate_update(update),
+        ActivityMessage::ErrorNotice(notice) => handler.on_error_notice(notice),
+        ActivityMessage::Heartbeat(heartbeat) => handler.on_heartbeat(heartbeat),
+    }
+}
+
+pub(crate) fn check_ack(message: &ActivityMessage) -> Result<(), Error> {
+    match message {
+        ActivityMessage::SubscribeActivityAck(ack) if !ack.payload.success => Err(Error::new(
+            ErrorType::AuthenticationError,
+            &format!("Activity subscription rejected: {}", ack.payload.details),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Drives `stream` until it closes, dispatching every message to `handler`.
+///
+/// Unparseable messages are logged and skipped. Any other error, including a
+/// rejected subscription, ends the session and is returned.
+pub async fn run_activity_handler<S, H>(mut stream: ActivityStream<S>, handler: &mut H) -> Result<(), Error>
+where
+    S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    H: ActivityHandler + ?Sized,
+{
+    while let Some(message) = stream.next().await {
+        let result = message.and_then(|message| {
+            check_ack(&message)?;
+            dispatch(handler, &message);
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => {}
+            Err(e) if e.error_type == ErrorType::ParseError => {
+                tracing::warn!("Skipping unparseable activity message: {}", e);
+            }
+            Err(e) => {
+                handler.on_disconnect(Some(&e));
+                return Err(e);
+            }
+        }
+    }
+
+    handler.on_disconnect(None);
+    Ok(())
+}
+
+/// Blocking counterpart of [`run_activity_handler`] for a `connect_websocket_blocking` socket.
+pub fn run_activity_handler_blocking<T, H>(
+    socket: &mut tungstenite::WebSocket<T>,
+    handler: &mut H,
+) -> Result<(), Error>
+where
+    T: Read + Write,
+    H: ActivityHandler + ?Sized,
+{
+    loop {
+        let result = match socket.read() {
+            Ok(Message::Text(text)) => parse_message(text).and_then(|message| {
+                check_ack(&message)?;
+                dispatch(handler, &message);
+                Ok(())
+            }),
+            Ok(Message::Close(_))
+            | Err(tungstenite::Error::ConnectionClosed)
+            | Err(tungstenite::Error::AlreadyClosed) => {
+                handler.on_disconnect(None);
+                return Ok(());
+            }
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.into()),
+        };
+
+        match result {
+            Ok(()) => {}
+            Err(e) if e.error_type == ErrorType::ParseError => {
+                tracing::warn!("Skipping unparseable activity message: {}", e);
+            }
+            Err(e) => {
+                handler.on_disconnect(Some(&e));
+                return Err(e);
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use crate::client::ClientOptions;
+use crate::error::{Error, ErrorType};
+use crate::websockets::handler::check_ack;
+use crate::websockets::open_blocking_websocket;
+use crate::websockets::payloads::{parse_message, ActivityMessage};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tungstenite::protocol::Message;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::WebSocket;
+
+#[cfg(feature = "sync")]
+use crate::authentication::fetch_new_token_blocking;
+#[cfg(feature = "sync")]
+use crate::client::sync_client::SyncClient;
+
+type BlockingSocket = WebSocket<MaybeTlsStream<TcpStream>>;
+type TokenRefresh = Box<dyn Fn() -> Result<String, Error> + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct ActivityIteratorOptions {
+    /// Longest a single read blocks, which bounds how quickly a shutdown is noticed.
+    pub read_timeout: Duration,
+    /// Reconnect when nothing at all has been received for this long.
+    pub heartbeat_timeout: Option<Duration>,
+    pub reconnect: bool,
+    /// Consecutive failed reconnects before giving up. `None` retries forever.
+    pub max_reconnect_attempts: Option<u32>,
+    /// Delay before the first reconnect, doubled after every failure up to 30 seconds.
+    pub reconnect_backoff: Duration,
+    /// Whether heartbeats are yielded or consumed silently.
+    pub yield_heartbeats: bool,
+}
+
+impl Default for ActivityIteratorOptions {
+    fn default() -> Self {
+        Self {
+            read_timeout: Duration::from_secs(1),
+            heartbeat_timeout: Some(Duration::from_secs(30)),
+            reconnect: true,
+            max_reconnect_attempts: Some(10),
+            reconnect_backoff: Duration::from_millis(500),
+            yield_heartbeats: false,
+        }
+    }
+}
+
+/// Stops an [`ActivityIterator`] from any thread. The iterator ends within one read timeout.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle(Arc<AtomicBool>);
+
+impl ShutdownHandle {
+    pub fn shutdown(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Blocking, self-reconnecting iterator over the activity feed.
+///
+/// Each reconnect subscribes again, so the server replays activity before the next
+/// `ReplayComplete`. A rejected subscription is yielded as an error and ends the iterator.
+///
+/// A fresh token is fetched before each reconnect, see [`ActivityIterator::refresh_token_with`].
+pub struct ActivityIterator {
+    client_options: ClientOptions,
+    token: String,
+    refresh_token: Option<TokenRefresh>,
+    options: ActivityIteratorOptions,
+    socket: Option<BlockingSocket>,
+    shutdown: ShutdownHandle,
+    last_received: Instant,
+    failed_reconnects: u32,
+    finished: bool,
+}
+
+fn set_read_timeout(socket: &mut BlockingSocket, timeout: Duration) -> Result<(), Error> {
+    match socket.get_mut() {
+        MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(timeout))?,
+        MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(Some(timeout))?,
+        MaybeTlsStream::Rustls(stream) => stream.get_ref().set_read_timeout(Some(timeout))?,
+        _ => tracing::warn!("Unable to set read timeout on websocket stream"),
+    }
+
+    Ok(())
+}
+
+impl ActivityIterator {
+    /// Connects with the client's token, fetching a new one with its credentials on reconnect.
+    #[cfg(feature = "sync")]
+    pub fn connect(client: &SyncClient, options: ActivityIteratorOptions) -> Result<Self, Error> {
+        let client_options = client.client_options.clone();
+        let activity = Self::connect_with(&client.client_options, &client.token, options)?;
+
+        Ok(activity.refresh_token_with(move || Ok(fetch_new_token_blocking(&client_options)?.access_token)))
+    }
+
+    /// Connects with explicit client options and token, without a [`SyncClient`].
+    /// The token is reused on reconnect unless [`ActivityIterator::refresh_token_with`] is set.
+    pub fn connect_with(
+        client_options: &ClientOptions,
+        token: &str,
+        options: ActivityIteratorOptions,
+    ) -> Result<Self, Error> {
+        let mut socket = open_blocking_websocket(client_options, token)?;
+        set_read_timeout(&mut socket, options.read_timeout)?;
+
+        Ok(Self {
+            client_options: client_options.clone(),
+            token: token.to_string(),
+            refresh_token: None,
+            options,
+            socket: Some(socket),
+            shutdown: ShutdownHandle::default(),
+            last_received: Instant::now(),
+            failed_reconnects: 0,
+            finished: false,
+        })
+    }
+
+    /// Fetches the token used by each reconnect. A failed fetch counts as a failed reconnect.
+    pub fn refresh_token_with(mut self, refresh: impl Fn() -> Result<String, Error> + Send + Sync + 'static) -> Self {
+        self.refresh_token = Some(Box::new(refresh));
+        self
+    }
+
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    fn close(&mut self) {
+        if let Some(mut socket) = self.socket.take() {
+            let _ = socket.close(None);
+            let _ = socket.flush();
+        }
+    }
+
+    /// Sleeps in read-timeout sized steps so a shutdown interrupts the backoff.
+    fn backoff(&self) {
+        let exponent = self.failed_reconnects.min(16);
+        let delay = (self.options.reconnect_backoff * 2u32.pow(exponent)).min(Duration::from_secs(30));
+        let deadline = Instant::now() + delay;
+
+        while !self.shutdown.is_shutdown() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            std::thread::sleep((deadline - now).min(self.options.read_timeout));
+        }
+    }
+
+    fn reconnect(&mut self) -> Result<(), Error> {
+        self.backoff();
+        if self.shutdown.is_shutdown() {
+            return Ok(());
+        }
+
+        tracing::info!("Reconnecting activity websocket, attempt {}", self.failed_reconnects + 1);
+
+        if let Some(refresh) = &self.refresh_token {
+            match refresh() {
+                Ok(token) => self.token = token,
+                Err(e) => {
+                    self.failed_reconnects += 1;
+                    return Err(e);
+                }
+            }
+        }
+
+        let result = open_blocking_websocket(&self.client_options, &self.token).and_then(|mut socket| {
+            set_read_timeout(&mut socket, self.options.read_timeout)?;
+            Ok(socket)
+        });
+
+        match result {
+            Ok(socket) => {
+                self.socket = Some(socket);
+                self.last_received = Instant::now();
+                self.failed_reconnects = 0;
+                Ok(())
+            }
+            Err(e) => {
+                self.failed_reconnects += 1;
+                Err(e)
+            }
+        }
+    }
+
+    /// Drops the current connection. Returns the error to yield when the iterator has to stop.
+    fn disconnected(&mut self, error: Option<Error>) -> Option<Error> {
+        self.socket = None;
+
+        if self.options.reconnect {
+            if let Some(e) = error {
+                tracing::warn!("Activity websocket disconnected: {}", e);
+            }
+            return None;
+        }
+
+        self.finished = true;
+        error
+    }
+}
+
+impl Iterator for ActivityIterator {
+    type Item = Result<ActivityMessage, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.finished {
+                return None;
+            }
+
+            if self.shutdown.is_shutdown() {
+                self.close();
+                self.finished = true;
+                return None;
+            }
+
+            let Some(socket) = self.socket.as_mut() else {
+                if let Err(e) = self.reconnect() {
+                    let exhausted = self
+                        .options
+                        .max_reconnect_attempts
+                        .is_some_and(|max| self.failed_reconnects >= max);
+
+                    if exhausted {
+                        self.finished = true;
+                        return Some(Err(e));
+                    }
+                    tracing::warn!("Activity websocket reconnect failed: {}", e);
+                }
+                continue;
+            };
+
+            let stopped = match socket.read() {
+                Ok(Message::Text(text)) => {
+                    self.last_received = Instant::now();
+
+                    match parse_message(text) {
+                        Ok(ActivityMessage::Heartbeat(_)) if !self.options.yield_heartbeats => continue,
+                        Ok(message) => {
+                            // Reconnecting with the same credentials would be rejected again.
+                            if let Err(e) = check_ack(&message) {
+                                self.close();
+                                self.finished = true;
+                                return Some(Err(e));
+                            }
+                            return Some(Ok(message));
+                        }
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                Ok(Message::Close(_))
+                | Err(tungstenite::Error::ConnectionClosed)
+                | Err(tungstenite::Error::AlreadyClosed) => self.disconnected(None),
+                Ok(_) => {
+                    self.last_received = Instant::now();
+                    continue;
+                }
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+                {
+                    let stale = self
+                        .options
+                        .heartbeat_timeout
+                        .is_some_and(|timeout| self.last_received.elapsed() > timeout);
+
+                    if !stale {
+                        continue;
+                    }
+
+                    self.close();
+                    self.disconnected(Some(Error::new(
+                        ErrorType::TimeoutError,
+                        "No activity received within the heartbeat timeout",
+                    )))
+                }
+                Err(e) => self.disconnected(Some(e.into())),
+            };
+
+            if let Some(e) = stopped {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl Drop for ActivityIterator {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
@@ -0,0 +1,233 @@
+use crate::client::AsyncClearstreetClient;
+use crate::error::Error;
+use crate::orders::create::{CreateOrderParams, CreateOrderResponse};
+use crate::websockets::payloads::{ActivityMessage, PayloadType};
+use chrono::Utc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+// Submissions and early updates older than this are assumed to never be matched.
+const PENDING_TTL_MS: i64 = 5 * 60 * 1000;
+
+/// Percentiles over the retained samples, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min_ms: i64,
+    pub p50_ms: i64,
+    pub p90_ms: i64,
+    pub p99_ms: i64,
+    pub max_ms: i64,
+}
+
+impl LatencySummary {
+    fn from_samples(samples: &VecDeque<i64>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<i64> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let percentile = |p: f64| {
+            let rank = ((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+            sorted[rank - 1]
+        };
+
+        Some(Self {
+            count: sorted.len(),
+            min_ms: sorted[0],
+            p50_ms: percentile(0.50),
+            p90_ms: percentile(0.90),
+            p99_ms: percentile(0.99),
+            max_ms: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+struct LatencyState {
+    window: usize,
+    receive: HashMap<PayloadType, VecDeque<i64>>,
+    round_trip: VecDeque<i64>,
+    submitted: PendingOrders,
+    first_updates: PendingOrders,
+    // Submissions sent through `LatencyTracker::create_order` still awaiting a response.
+    in_flight: usize,
+}
+
+/// Order timestamps waiting for their counterpart, expired oldest first.
+#[derive(Default)]
+struct PendingOrders {
+    times: HashMap<String, i64>,
+    order: VecDeque<(i64, String)>,
+}
+
+impl PendingOrders {
+    fn insert(&mut self, order_id: &str, at: i64) {
+        if !self.times.contains_key(order_id) {
+            self.times.insert(order_id.to_string(), at);
+            self.order.push_back((at, order_id.to_string()));
+        }
+    }
+
+    fn remove(&mut self, order_id: &str) -> Option<i64> {
+        self.times.remove(order_id)
+    }
+
+    fn expire(&mut self, now: i64) {
+        while let Some((at, _)) = self.order.front() {
+            if now - *at < PENDING_TTL_MS {
+                break;
+            }
+            if let Some((at, order_id)) = self.order.pop_front() {
+                if self.times.get(&order_id) == Some(&at) {
+                    self.times.remove(&order_id);
+                }
+            }
+        }
+        // Entries matched before expiring leave stale queue slots behind.
+        if self.order.len() > 2 * self.times.len() + 64 {
+            let times = &self.times;
+            self.order.retain(|(at, order_id)| times.get(order_id) == Some(at));
+        }
+    }
+}
+
+fn push_sample(samples: &mut VecDeque<i64>, window: usize, sample: i64) {
+    if samples.len() == window {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+/// Counts a submission as in flight until dropped, including when the call is abandoned.
+struct InFlight<'a>(&'a Mutex<LatencyState>);
+
+impl<'a> InFlight<'a> {
+    fn new(state: &'a Mutex<LatencyState>) -> Self {
+        state.lock().unwrap().in_flight += 1;
+        Self(state)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.0.lock() {
+            state.in_flight -= 1;
+        }
+    }
+}
+
+/// Rolling latency statistics for the activity feed. Cheap to clone, all clones share state.
+///
+/// Receive latency is local receive time minus the server `timestamp` of each live
+/// message. Order round trip is the time from `create_order` submission to the first
+/// `OrderUpdate` for that order. An update that beats the `create_order` response is only
+/// matched for orders sent through [`LatencyTracker::create_order`].
+#[derive(Clone)]
+pub struct LatencyTracker {
+    state: Arc<Mutex<LatencyState>>,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl LatencyTracker {
+    /// Keeps the most recent `window` samples per series.
+    pub fn new(window: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(LatencyState {
+                window: window.max(1),
+                receive: HashMap::new(),
+                round_trip: VecDeque::new(),
+                submitted: PendingOrders::default(),
+                first_updates: PendingOrders::default(),
+                in_flight: 0,
+            })),
+        }
+    }
+
+    pub fn record_message(&self, message: &ActivityMessage, received_at: i64) {
+        let payload_type = message.payload_type();
+        let latency_ms = received_at - message.timestamp();
+
+        let mut state = self.state.lock().unwrap();
+        let window = state.window;
+        push_sample(state.receive.entry(payload_type.clone()).or_default(), window, latency_ms);
+        tracing::trace!(message_type = ?payload_type, latency_ms, "Activity message received");
+
+        if let ActivityMessage::OrderUpdate(update) = message {
+            let order_id = &update.payload.data.order_id;
+
+            match state.submitted.remove(order_id) {
+                Some(submitted_at) => {
+                    let round_trip_ms = received_at - submitted_at;
+                    push_sample(&mut state.round_trip, window, round_trip_ms);
+                    tracing::debug!(order_id = %order_id, round_trip_ms, "Order round trip");
+                }
+                // The update may beat the create_order response, keep it until the submission is registered.
+                None if state.in_flight > 0 => state.first_updates.insert(order_id, received_at),
+                None => {}
+            }
+        }
+
+        state.first_updates.expire(received_at);
+    }
+
+    /// Registers when `order_id` was submitted, for round trip measurement.
+    pub fn record_order_submitted(&self, order_id: &str, submitted_at: i64) {
+        let mut state = self.state.lock().unwrap();
+        let window = state.window;
+
+        match state.first_updates.remove(order_id) {
+            Some(received_at) => {
+                let round_trip_ms = received_at - submitted_at;
+                push_sample(&mut state.round_trip, window, round_trip_ms);
+                tracing::debug!(order_id = %order_id, round_trip_ms, "Order round trip");
+            }
+            None => state.submitted.insert(order_id, submitted_at),
+        }
+
+        state.submitted.expire(submitted_at);
+    }
+
+    pub fn receive_latency(&self, payload_type: &PayloadType) -> Option<LatencySummary> {
+        let state = self.state.lock().unwrap();
+        state.receive.get(payload_type).and_then(LatencySummary::from_samples)
+    }
+
+    pub fn receive_latencies(&self) -> HashMap<PayloadType, LatencySummary> {
+        let state = self.state.lock().unwrap();
+        state
+            .receive
+            .iter()
+            .filter_map(|(payload_type, samples)| {
+                LatencySummary::from_samples(samples).map(|summary| (payload_type.clone(), summary))
+            })
+            .collect()
+    }
+
+    pub fn order_round_trip(&self) -> Option<LatencySummary> {
+        let state = self.state.lock().unwrap();
+        LatencySummary::from_samples(&state.round_trip)
+    }
+
+    /// Submits an order and registers it for round trip measurement.
+    pub async fn create_order(
+        &self,
+        client: &dyn AsyncClearstreetClient,
+        params: CreateOrderParams,
+    ) -> Result<CreateOrderResponse, Error> {
+        let submitted_at = Utc::now().timestamp_millis();
+        let in_flight = InFlight::new(&self.state);
+        let result = client.create_order(params).await;
+        drop(in_flight);
+
+        let response = result?;
+        self.record_order_submitted(&response.order_id, submitted_at);
+        Ok(response)
+    }
+}
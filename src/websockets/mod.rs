@@ -1,12 +1,29 @@
+pub mod broadcast;
+pub mod handler;
+pub mod iterator;
+pub mod latency;
 pub mod payloads;
+pub mod recorder;
+pub mod session;
+pub mod stream;
 use crate::error::Error;
 
 use crate::client::async_client::AsyncClient;
+use crate::client::ClientOptions;
+pub use crate::websockets::latency::{LatencySummary, LatencyTracker};
 pub use crate::websockets::payloads::{
-    ActivityMessage, BuyingPowerUpdate, ErrorNotice, LocateInventoryUpdate, OrderUpdate,
+    ActivityMessage, BuyingPowerUpdate, ErrorCategory, ErrorNotice, Heartbeat, LocateInventoryUpdate, OrderUpdate,
     PayloadType, PositionUpdate, ReplayComplete, SubscribeActivity, SubscribeActivityAck,
     SubscribeActivityPayload, TradeNotice,
 };
+pub use crate::websockets::broadcast::{
+    ActivityBroadcaster, ActivityFilter, ActivitySubscription, BroadcastOptions, LagPolicy,
+};
+pub use crate::websockets::handler::{dispatch, run_activity_handler, run_activity_handler_blocking, ActivityHandler};
+pub use crate::websockets::iterator::{ActivityIterator, ActivityIteratorOptions, ShutdownHandle};
+pub use crate::websockets::recorder::{ReplaySpeed, SessionRecorder, SessionReplayer};
+pub use crate::websockets::session::{AccountEvent, MultiAccountSession, SessionEvent, SessionOptions};
+pub use crate::websockets::stream::{ActivitySnapshot, ActivityStream};
 
 #[cfg(feature = "sync")]
 use crate::client::sync_client::SyncClient;
@@ -34,45 +51,55 @@ pub async fn connect_websocket(
     let token: &str = &client.token;
     let account_id: &str = &client.client_options.account_id;
 
-    let auth_msg = SubscribeActivity {
+    let auth_msg = activity_subscription_message(PayloadType::SubscribeActivity, token, account_id)?;
+
+    ws_stream
+        .send(auth_msg)
+        .await?;
+
+    Ok(ws_stream)
+}
+
+/// Builds a `subscribe-activity` message for `account_id`.
+pub fn activity_subscription_message(
+    payload_type: PayloadType,
+    token: &str,
+    account_id: &str,
+) -> Result<Message, Error> {
+    let msg = SubscribeActivity {
         authorization: token.to_string(),
         payload: SubscribeActivityPayload {
-            payload_type: PayloadType::SubscribeActivity,
+            payload_type,
             account_id: account_id.to_string(),
         },
     };
 
-    let msg_json = serde_json::to_string(&auth_msg)?;
-
-    ws_stream
-        .send(Message::Text(Utf8Bytes::from(msg_json)))
-        .await?;
+    let msg_json = serde_json::to_string(&msg)?;
 
-    Ok(ws_stream)
+    Ok(Message::Text(Utf8Bytes::from(msg_json)))
 }
 
 #[cfg(feature = "sync")]
 pub fn connect_websocket_blocking(
     client: &SyncClient,
 ) -> Result<tungstenite::protocol::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>>, Error> {
-    tracing::debug!("Creating blocking websocket session");
+    open_blocking_websocket(&client.client_options, &client.token)
+}
 
-    let (mut ws_stream, _response) = tungstenite::connect(&client.client_options.websocket_url)?;
+/// Opens a blocking activity session for `client_options.account_id` authorised by `token`.
+pub(crate) fn open_blocking_websocket(
+    client_options: &ClientOptions,
+    token: &str,
+) -> Result<tungstenite::protocol::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>>, Error> {
+    tracing::debug!("Creating blocking websocket session");
 
-    let token = &client.token;
-    let account_id = &client.client_options.account_id;
+    let (mut ws_stream, _response) = tungstenite::connect(&client_options.websocket_url)?;
 
-    let auth_msg = SubscribeActivity {
-        authorization: token.to_string(),
-        payload: SubscribeActivityPayload {
-            payload_type: PayloadType::SubscribeActivity,
-            account_id: account_id.to_string(),
-        },
-    };
+    let account_id = &client_options.account_id;
 
-    let msg_json = serde_json::to_string(&auth_msg)?;
+    let auth_msg = activity_subscription_message(PayloadType::SubscribeActivity, token, account_id)?;
 
-    ws_stream.send(Message::Text(Utf8Bytes::from(msg_json)))?;
+    ws_stream.send(auth_msg)?;
 
     Ok(ws_stream)
 }
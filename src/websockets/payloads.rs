@@ -60,8 +60,9 @@ pub fn parse_message(message: Utf8Bytes) -> Result<ActivityMessage, Error> {
             ActivityMessage::Heartbeat(parsed_message)
         }
         _ => {
-            tracing::warn!("Unknown message type received: {:?}", parsed_payload_type);
-            return Err(Error::new(ErrorType::ParseError, "Unknown message type"));
+            tracing::warn!("Unknown message type received: {}", parsed_payload_type);
+            let msg = format!("Unknown message type: {}", parsed_payload_type);
+            return Err(Error::new(ErrorType::ParseError, &msg));
         }
     };
 
@@ -69,34 +70,26 @@ pub fn parse_message(message: Utf8Bytes) -> Result<ActivityMessage, Error> {
 }
 
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub enum PayloadType {
-    #[serde(rename = "subscribe-activity")]
-    SubscribeActivity,
-    #[serde(rename = "subscribe-activity-ack")]
-    SubscribeActivityAck,
-    #[serde(rename = "replay-complete")]
-    ReplayComplete,
-    #[serde(rename = "order-update")]
-    OrderUpdate,
-    #[serde(rename = "trade-notice")]
-    TradeNotice,
-    #[serde(rename = "position-update")]
-    PositionUpdate,
-    #[serde(rename = "buying-power-update")]
-    BuyingPowerUpdate,
-    #[serde(rename = "locate-inventory-update")]
-    LocateInventoryUpdate,
-    #[serde(rename = "heartbeat")]
-    Heartbeat,
-    #[serde(rename = "error-notice")]
-    ErrorNotice,
+wire_enum! {
+    pub enum PayloadType {
+        SubscribeActivity => "subscribe-activity",
+        SubscribeActivityAck => "subscribe-activity-ack",
+        ReplayComplete => "replay-complete",
+        OrderUpdate => "order-update",
+        TradeNotice => "trade-notice",
+        PositionUpdate => "position-update",
+        BuyingPowerUpdate => "buying-power-update",
+        LocateInventoryUpdate => "locate-inventory-update",
+        Heartbeat => "heartbeat",
+        ErrorNotice => "error-notice",
+    }
 }
 
 
 // All message formats and types, along with their serialization
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ActivityMessage {
     SubscribeActivityAck(SubscribeActivityAck),
     ReplayComplete(ReplayComplete),
@@ -109,6 +102,83 @@ pub enum ActivityMessage {
     Heartbeat(Heartbeat)
 }
 
+impl ActivityMessage {
+    pub fn payload_type(&self) -> PayloadType {
+        match self {
+            ActivityMessage::SubscribeActivityAck(_) => PayloadType::SubscribeActivityAck,
+            ActivityMessage::ReplayComplete(_) => PayloadType::ReplayComplete,
+            ActivityMessage::OrderUpdate(_) => PayloadType::OrderUpdate,
+            ActivityMessage::TradeNotice(_) => PayloadType::TradeNotice,
+            ActivityMessage::PositionUpdate(_) => PayloadType::PositionUpdate,
+            ActivityMessage::BuyingPowerUpdate(_) => PayloadType::BuyingPowerUpdate,
+            ActivityMessage::LocateInventoryUpdate(_) => PayloadType::LocateInventoryUpdate,
+            ActivityMessage::ErrorNotice(_) => PayloadType::ErrorNotice,
+            ActivityMessage::Heartbeat(_) => PayloadType::Heartbeat,
+        }
+    }
+
+    /// Serializes the message in its wire format, as sent by the server.
+    pub fn to_json(&self) -> Result<String, Error> {
+        let json = match self {
+            ActivityMessage::SubscribeActivityAck(m) => serde_json::to_string(m),
+            ActivityMessage::ReplayComplete(m) => serde_json::to_string(m),
+            ActivityMessage::OrderUpdate(m) => serde_json::to_string(m),
+            ActivityMessage::TradeNotice(m) => serde_json::to_string(m),
+            ActivityMessage::PositionUpdate(m) => serde_json::to_string(m),
+            ActivityMessage::BuyingPowerUpdate(m) => serde_json::to_string(m),
+            ActivityMessage::LocateInventoryUpdate(m) => serde_json::to_string(m),
+            ActivityMessage::ErrorNotice(m) => serde_json::to_string(m),
+            ActivityMessage::Heartbeat(m) => serde_json::to_string(m),
+        }?;
+
+        Ok(json)
+    }
+
+    /// Server timestamp of the message, in milliseconds.
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            ActivityMessage::SubscribeActivityAck(m) => m.timestamp,
+            ActivityMessage::ReplayComplete(m) => m.timestamp,
+            ActivityMessage::OrderUpdate(m) => m.timestamp,
+            ActivityMessage::TradeNotice(m) => m.timestamp,
+            ActivityMessage::PositionUpdate(m) => m.timestamp,
+            ActivityMessage::BuyingPowerUpdate(m) => m.timestamp,
+            ActivityMessage::LocateInventoryUpdate(m) => m.timestamp,
+            ActivityMessage::ErrorNotice(m) => m.timestamp,
+            ActivityMessage::Heartbeat(m) => m.timestamp,
+        }
+    }
+
+    /// Symbol the message refers to, for order, trade and position messages.
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            ActivityMessage::OrderUpdate(m) => Some(&m.payload.data.symbol),
+            ActivityMessage::TradeNotice(m) => Some(&m.payload.data.symbol),
+            ActivityMessage::PositionUpdate(m) => Some(&m.payload.data.symbol),
+            _ => None,
+        }
+    }
+
+    /// Account the message belongs to, for order, trade and position messages.
+    pub fn account_id(&self) -> Option<&str> {
+        match self {
+            ActivityMessage::OrderUpdate(m) => Some(&m.payload.data.account_id),
+            ActivityMessage::TradeNotice(m) => Some(&m.payload.data.account_id),
+            ActivityMessage::PositionUpdate(m) => Some(&m.payload.data.account_id),
+            _ => None,
+        }
+    }
+
+    /// Order the message refers to, for order and trade messages.
+    pub fn order_id(&self) -> Option<&str> {
+        match self {
+            ActivityMessage::OrderUpdate(m) => Some(&m.payload.data.order_id),
+            ActivityMessage::TradeNotice(m) => Some(&m.payload.data.order_id),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Heartbeat {
     pub timestamp: i64,
@@ -134,6 +204,60 @@ pub struct ErrorNoticePayload {
     pub details: String,
 }
 
+/// What an error notice is about, as far as its details tell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ErrorCategory {
+    Authentication,
+    Validation,
+    Rejected,
+    RateLimit,
+    Internal,
+    Unknown,
+}
+
+impl ErrorNotice {
+    pub fn message(&self) -> &str {
+        &self.payload.details
+    }
+
+    /// Best-effort category guessed from the wording of `details`, the only field the
+    /// notice carries. Wording the guess does not recognise is `Unknown`.
+    pub fn category(&self) -> ErrorCategory {
+        category_for_text(&self.payload.details)
+    }
+
+    pub fn to_error(&self) -> Error {
+        Error::from(self)
+    }
+}
+
+/// Matches whole words and phrases, so e.g. "separate" is not read as a rate limit.
+fn category_for_text(text: &str) -> ErrorCategory {
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    let has = |phrase: &str| {
+        let phrase: Vec<&str> = phrase.split(' ').collect();
+        words.windows(phrase.len()).any(|window| window == phrase.as_slice())
+    };
+
+    if ["unauthorized", "unauthenticated", "forbidden", "authentication failed", "invalid token", "expired token"]
+        .iter()
+        .any(|p| has(p))
+    {
+        ErrorCategory::Authentication
+    } else if ["rate limit", "rate limited", "throttled", "too many requests"].iter().any(|p| has(p)) {
+        ErrorCategory::RateLimit
+    } else if ["rejected", "reject"].iter().any(|p| has(p)) {
+        ErrorCategory::Rejected
+    } else if ["invalid", "missing", "malformed"].iter().any(|p| has(p)) {
+        ErrorCategory::Validation
+    } else if ["internal error", "internal server error"].iter().any(|p| has(p)) {
+        ErrorCategory::Internal
+    } else {
+        ErrorCategory::Unknown
+    }
+}
+
 // All allowed outgoing message formats and types, along with their serialization
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SubscribeActivity {
@@ -0,0 +1,242 @@
+use crate::error::{Error, ErrorType};
+use crate::websockets::payloads::{parse_message, ActivityMessage};
+use crate::websockets::stream::ActivityStream;
+use chrono::Utc;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::task::{Context, Poll};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+#[cfg(feature = "async")]
+use crate::client::async_client::AsyncClient;
+#[cfg(feature = "async")]
+use crate::websockets::connect_websocket;
+#[cfg(feature = "async")]
+use crate::websockets::stream::ActivitySocket;
+
+/// One line of a session recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Local receive time in milliseconds.
+    pub received_at: i64,
+    /// Raw text frame as sent by the server.
+    pub frame: String,
+}
+
+/// Appends raw activity frames to a JSONL file, one [`RecordedFrame`] per line.
+///
+/// Frames are handed to a dedicated writer thread, so recording never blocks the task
+/// polling the websocket.
+pub struct SessionRecorder {
+    sender: Option<mpsc::Sender<RecordedFrame>>,
+    writer: Option<JoinHandle<Result<(), Error>>>,
+}
+
+fn write_frames(file: File, frames: mpsc::Receiver<RecordedFrame>) -> Result<(), Error> {
+    let mut writer = BufWriter::new(file);
+
+    for frame in frames {
+        writeln!(writer, "{}", serde_json::to_string(&frame)?)?;
+        // Flush every frame so a crashing process still leaves a usable recording.
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+impl SessionRecorder {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::create(path)?;
+        let (sender, frames) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("clearstreet-recorder".to_string())
+            .spawn(move || write_frames(file, frames))?;
+
+        Ok(Self {
+            sender: Some(sender),
+            writer: Some(writer),
+        })
+    }
+
+    /// Queues `frame` for writing. Fails once the writer has stopped on an I/O error.
+    pub fn record(&mut self, frame: &str) -> Result<(), Error> {
+        let frame = RecordedFrame {
+            received_at: Utc::now().timestamp_millis(),
+            frame: frame.to_string(),
+        };
+
+        match &self.sender {
+            Some(sender) if sender.send(frame).is_ok() => Ok(()),
+            _ => Err(Error::new(ErrorType::IoError, "Session recorder has stopped writing")),
+        }
+    }
+
+    /// Waits for every queued frame to be written, returning the writer's error if it failed.
+    ///
+    /// This blocks the calling thread; dropping the recorder finishes writing in the background.
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.sender.take();
+        match self.writer.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(Error::new(ErrorType::InternalError, "Session recorder thread panicked")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Passes websocket frames through unchanged while recording every text frame.
+pub struct RecordingStream<S> {
+    inner: S,
+    recorder: SessionRecorder,
+}
+
+impl<S> RecordingStream<S> {
+    pub fn new(inner: S, recorder: SessionRecorder) -> Self {
+        Self { inner, recorder }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    pub fn into_parts(self) -> (S, SessionRecorder) {
+        (self.inner, self.recorder)
+    }
+}
+
+impl<S> Stream for RecordingStream<S>
+where
+    S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let polled = this.inner.poll_next_unpin(cx);
+
+        if let Poll::Ready(Some(Ok(Message::Text(text)))) = &polled {
+            if let Err(e) = this.recorder.record(text.as_str()) {
+                tracing::error!("Failed to record activity frame: {}", e);
+            }
+        }
+
+        polled
+    }
+}
+
+/// Connects like `connect_activity_stream`, recording every frame to `path`.
+#[cfg(feature = "async")]
+pub async fn connect_recorded_activity_stream<P: AsRef<Path>>(
+    client: &AsyncClient,
+    path: P,
+) -> Result<ActivityStream<RecordingStream<ActivitySocket>>, Error> {
+    let recorder = SessionRecorder::create(path)?;
+    let ws_stream = connect_websocket(client).await?;
+    Ok(ActivityStream::new(RecordingStream::new(ws_stream, recorder)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Keep the gaps between frames as they were recorded.
+    Original,
+    /// Divide the recorded gaps by the given factor.
+    Accelerated(f64),
+    /// No delay between frames.
+    AsFastAsPossible,
+}
+
+impl ReplaySpeed {
+    fn delay(&self, gap_ms: i64) -> Option<Duration> {
+        let gap_ms = gap_ms.max(0) as f64;
+        let delay_ms = match self {
+            ReplaySpeed::Original => gap_ms,
+            ReplaySpeed::Accelerated(factor) if factor.is_finite() && *factor > 0.0 => gap_ms / factor,
+            ReplaySpeed::Accelerated(_) | ReplaySpeed::AsFastAsPossible => 0.0,
+        };
+
+        // Gaps too long for a `Duration`, e.g. from a tiny factor, replay without a delay.
+        Duration::try_from_secs_f64(delay_ms / 1000.0)
+            .ok()
+            .filter(|delay| !delay.is_zero())
+    }
+}
+
+pub type ReplayFrames =
+    Pin<Box<dyn Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Send>>;
+
+/// Plays back a recording made by [`SessionRecorder`].
+#[derive(Debug, Clone)]
+pub struct SessionReplayer {
+    frames: Vec<RecordedFrame>,
+}
+
+impl SessionReplayer {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut frames = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            frames.push(serde_json::from_str::<RecordedFrame>(&line)?);
+        }
+
+        Ok(Self { frames })
+    }
+
+    pub fn from_frames(frames: Vec<RecordedFrame>) -> Self {
+        Self { frames }
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Replays the recording as websocket frames, paced according to `speed`.
+    pub fn into_stream(self, speed: ReplaySpeed) -> ReplayFrames {
+        let frames = self.frames.into_iter();
+
+        let stream = futures_util::stream::unfold(
+            (frames, None::<i64>),
+            move |(mut frames, previous)| async move {
+                let frame = frames.next()?;
+
+                if let Some(delay) = previous.and_then(|previous| speed.delay(frame.received_at - previous)) {
+                    tokio::time::sleep(delay).await;
+                }
+
+                let received_at = frame.received_at;
+                Some((Ok(Message::Text(frame.frame.into())), (frames, Some(received_at))))
+            },
+        );
+
+        Box::pin(stream)
+    }
+
+    pub fn into_activity_stream(self, speed: ReplaySpeed) -> ActivityStream<ReplayFrames> {
+        ActivityStream::new(self.into_stream(speed))
+    }
+
+    /// Blocking replay through `parse_message`, sleeping the calling thread between frames.
+    pub fn replay_blocking(self, speed: ReplaySpeed) -> impl Iterator<Item = Result<ActivityMessage, Error>> {
+        let mut previous: Option<i64> = None;
+
+        self.frames.into_iter().map(move |frame| {
+            if let Some(delay) = previous.and_then(|previous| speed.delay(frame.received_at - previous)) {
+                std::thread::sleep(delay);
+            }
+            previous = Some(frame.received_at);
+
+            parse_message(frame.frame.into())
+        })
+    }
+}
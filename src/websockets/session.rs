@@ -0,0 +1,359 @@
+use crate::client::async_client::AsyncClient;
+use crate::client::{AsyncClearstreetClient, ClientOptions};
+use crate::error::{Error, ErrorType};
+use crate::websockets::activity_subscription_message;
+use crate::websockets::payloads::{parse_message, ActivityMessage, PayloadType};
+use crate::websockets::stream::ActivitySocket;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// An activity message tagged with the account it belongs to.
+///
+/// Order, trade and position messages carry their own account. Acks and
+/// `ReplayComplete` are attributed to subscriptions in the order they were sent.
+/// Heartbeats and error notices are connection-wide and have no account.
+#[derive(Debug)]
+pub struct AccountEvent {
+    pub account_id: Option<String>,
+    pub message: ActivityMessage,
+    /// Set when the message is a rejected ack for an account resubscribed after a
+    /// reconnect. The account is kept and subscribed again on the next reconnect.
+    pub error: Option<Error>,
+}
+
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum SessionEvent {
+    Activity(AccountEvent),
+    /// The connection dropped. Subscriptions are restored on reconnect.
+    Disconnected(Option<Error>),
+    /// A new connection is up and every subscription has been sent again.
+    Reconnected,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    pub reconnect_backoff: Duration,
+    pub max_reconnect_backoff: Duration,
+    /// Events buffered before the connection stops being read.
+    pub event_capacity: usize,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            reconnect_backoff: Duration::from_millis(500),
+            max_reconnect_backoff: Duration::from_secs(30),
+            event_capacity: 1024,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum SessionCommand {
+    Subscribe(String),
+    Unsubscribe,
+    Shutdown,
+}
+
+struct SessionTask {
+    websocket_url: String,
+    token: String,
+    token_client: Arc<dyn AsyncClearstreetClient>,
+    options: SessionOptions,
+    accounts: Arc<Mutex<BTreeSet<String>>>,
+    commands: mpsc::UnboundedReceiver<SessionCommand>,
+    events: mpsc::Sender<SessionEvent>,
+    /// Accounts awaiting an ack, and whether each was resubscribed after a reconnect.
+    pending_acks: VecDeque<(String, bool)>,
+    pending_replays: VecDeque<String>,
+}
+
+enum Disconnect {
+    Shutdown,
+    Dropped(Option<Error>),
+    /// An account was removed, so the connection is replaced by one without it.
+    Resubscribe,
+}
+
+impl SessionTask {
+    async fn send_subscription(
+        &mut self,
+        ws_stream: &mut ActivitySocket,
+        account_id: &str,
+        resubscribe: bool,
+    ) -> Result<(), Error> {
+        let message = activity_subscription_message(PayloadType::SubscribeActivity, &self.token, account_id)?;
+        ws_stream.send(message).await?;
+        self.pending_acks.push_back((account_id.to_string(), resubscribe));
+
+        Ok(())
+    }
+
+    async fn connect(&mut self, resubscribe: bool) -> Result<ActivitySocket, Error> {
+        let (mut ws_stream, _) = connect_async(&self.websocket_url).await?;
+
+        self.pending_acks.clear();
+        self.pending_replays.clear();
+
+        let accounts: Vec<String> = self.accounts.lock().unwrap().iter().cloned().collect();
+        for account_id in accounts {
+            self.send_subscription(&mut ws_stream, &account_id, resubscribe).await?;
+        }
+
+        Ok(ws_stream)
+    }
+
+    /// Fetches a fresh token, then connects and resubscribes every account with it.
+    async fn reconnect(&mut self) -> Result<ActivitySocket, Error> {
+        self.token = self.token_client.fetch_new_token().await?.access_token;
+        self.connect(true).await
+    }
+
+    fn tag(&mut self, message: ActivityMessage) -> Option<AccountEvent> {
+        let mut error = None;
+        let account_id = match &message {
+            ActivityMessage::SubscribeActivityAck(ack) => {
+                let pending = self.pending_acks.pop_front();
+                if let Some((account_id, resubscribe)) = &pending {
+                    if ack.payload.success {
+                        self.pending_replays.push_back(account_id.clone());
+                    } else if *resubscribe {
+                        // The account was accepted before, so keep it for the next reconnect.
+                        let msg = format!(
+                            "Resubscribing account {} after a reconnect was rejected: {}",
+                            account_id, ack.payload.details
+                        );
+                        error = Some(Error::new(ErrorType::AuthenticationError, &msg));
+                    } else {
+                        // Not restored on reconnect; the caller sees the rejected ack and may retry.
+                        self.accounts.lock().unwrap().remove(account_id);
+                    }
+                }
+                pending.map(|(account_id, _)| account_id)
+            }
+            ActivityMessage::ReplayComplete(_) => self.pending_replays.pop_front(),
+            other => match other.account_id() {
+                Some(account_id) => {
+                    // Drop in-flight events for accounts that have since been unsubscribed.
+                    if !self.accounts.lock().unwrap().contains(account_id) {
+                        return None;
+                    }
+                    Some(account_id.to_string())
+                }
+                None => None,
+            },
+        };
+
+        Some(AccountEvent {
+            account_id,
+            message,
+            error,
+        })
+    }
+
+    async fn serve(&mut self, ws_stream: &mut ActivitySocket) -> Disconnect {
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => {
+                    let result = match command {
+                        Some(SessionCommand::Subscribe(account_id)) => {
+                            self.send_subscription(ws_stream, &account_id, false).await
+                        }
+                        Some(SessionCommand::Unsubscribe) => {
+                            let _ = ws_stream.close(None).await;
+                            return Disconnect::Resubscribe;
+                        }
+                        Some(SessionCommand::Shutdown) | None => {
+                            let _ = ws_stream.close(None).await;
+                            return Disconnect::Shutdown;
+                        }
+                    };
+
+                    if let Err(e) = result {
+                        return Disconnect::Dropped(Some(e));
+                    }
+                }
+                frame = ws_stream.next() => match frame {
+                    Some(Ok(Message::Text(text))) => match parse_message(text) {
+                        Ok(message) => {
+                            if let Some(event) = self.tag(message) {
+                                if self.events.send(SessionEvent::Activity(event)).await.is_err() {
+                                    return Disconnect::Shutdown;
+                                }
+                            }
+                        }
+                        Err(e) => tracing::warn!("Dropping unparseable activity message: {}", e),
+                    },
+                    Some(Ok(Message::Close(_))) | None => return Disconnect::Dropped(None),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Disconnect::Dropped(Some(e.into())),
+                },
+            }
+        }
+    }
+
+    async fn run(mut self, mut ws_stream: ActivitySocket) {
+        let mut backoff = self.options.reconnect_backoff;
+
+        loop {
+            let mut delay = backoff;
+            match self.serve(&mut ws_stream).await {
+                Disconnect::Shutdown => return,
+                Disconnect::Dropped(error) => {
+                    tracing::warn!("Activity session disconnected: {:?}", error);
+                    if self.events.send(SessionEvent::Disconnected(error)).await.is_err() {
+                        return;
+                    }
+                }
+                Disconnect::Resubscribe => delay = Duration::ZERO,
+            }
+
+            ws_stream = loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    command = self.commands.recv() => match command {
+                        Some(SessionCommand::Shutdown) | None => return,
+                        // Already reflected in the shared account set, applied on reconnect.
+                        Some(_) => continue,
+                    },
+                }
+
+                match self.reconnect().await {
+                    Ok(ws_stream) => break ws_stream,
+                    Err(e) => {
+                        tracing::warn!("Activity session reconnect failed: {}", e);
+                        delay = backoff;
+                        backoff = (backoff * 2).min(self.options.max_reconnect_backoff);
+                    }
+                }
+            };
+
+            backoff = self.options.reconnect_backoff;
+            if self.events.send(SessionEvent::Reconnected).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// One websocket connection carrying activity for any number of accounts.
+///
+/// Accounts can be added and removed while connected. After a dropped connection the
+/// session fetches a fresh token, reconnects with backoff and subscribes every current
+/// account again.
+///
+/// The activity protocol only defines `subscribe-activity`, so removing an account
+/// replaces the connection with one subscribed to the remaining accounts, followed by
+/// [`SessionEvent::Reconnected`]. An account whose first subscription is rejected is
+/// removed. A rejected resubscription keeps the account and sets [`AccountEvent::error`].
+pub struct MultiAccountSession {
+    accounts: Arc<Mutex<BTreeSet<String>>>,
+    commands: mpsc::UnboundedSender<SessionCommand>,
+    events: mpsc::Receiver<SessionEvent>,
+    task: JoinHandle<()>,
+}
+
+impl MultiAccountSession {
+    pub async fn connect(
+        client: &AsyncClient,
+        account_ids: &[&str],
+        options: SessionOptions,
+    ) -> Result<Self, Error> {
+        Self::connect_with(
+            &client.client_options,
+            &client.token,
+            Arc::new(client.clone()),
+            account_ids,
+            options,
+        )
+        .await
+    }
+
+    /// Connects with explicit client options and token. `token_client` supplies a fresh
+    /// token before each reconnect.
+    pub async fn connect_with(
+        client_options: &ClientOptions,
+        token: &str,
+        token_client: Arc<dyn AsyncClearstreetClient>,
+        account_ids: &[&str],
+        options: SessionOptions,
+    ) -> Result<Self, Error> {
+        let accounts = Arc::new(Mutex::new(
+            account_ids.iter().map(|a| a.to_string()).collect::<BTreeSet<String>>(),
+        ));
+        let (command_sender, command_receiver) = mpsc::unbounded_channel();
+        let (event_sender, event_receiver) = mpsc::channel(options.event_capacity.max(1));
+
+        let mut task = SessionTask {
+            websocket_url: client_options.websocket_url.clone(),
+            token: token.to_string(),
+            token_client,
+            options,
+            accounts: accounts.clone(),
+            commands: command_receiver,
+            events: event_sender,
+            pending_acks: VecDeque::new(),
+            pending_replays: VecDeque::new(),
+        };
+
+        let ws_stream = task.connect(false).await?;
+        let task = tokio::spawn(task.run(ws_stream));
+
+        Ok(Self {
+            accounts,
+            commands: command_sender,
+            events: event_receiver,
+            task,
+        })
+    }
+
+    fn send(&self, command: SessionCommand) -> Result<(), Error> {
+        self.commands
+            .send(command)
+            .map_err(|_| Error::new(ErrorType::ChannelError, "Activity session has stopped"))
+    }
+
+    pub fn subscribe(&self, account_id: &str) -> Result<(), Error> {
+        if !self.accounts.lock().unwrap().insert(account_id.to_string()) {
+            return Ok(());
+        }
+        self.send(SessionCommand::Subscribe(account_id.to_string()))
+    }
+
+    pub fn unsubscribe(&self, account_id: &str) -> Result<(), Error> {
+        if !self.accounts.lock().unwrap().remove(account_id) {
+            return Ok(());
+        }
+        self.send(SessionCommand::Unsubscribe)
+    }
+
+    pub fn accounts(&self) -> Vec<String> {
+        self.accounts.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Next session event, or `None` once the session has shut down.
+    pub async fn recv(&mut self) -> Option<SessionEvent> {
+        self.events.recv().await
+    }
+
+    pub fn shutdown(&self) {
+        let _ = self.commands.send(SessionCommand::Shutdown);
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for MultiAccountSession {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::{Error, ErrorType};
+use crate::orders::Order;
+use crate::positions::Position;
+use crate::trades::Trade;
+use crate::websockets::latency::LatencyTracker;
+use crate::websockets::payloads::{parse_message, ActivityMessage};
+use chrono::Utc;
+use futures_util::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+#[cfg(feature = "async")]
+use crate::client::async_client::AsyncClient;
+#[cfg(feature = "async")]
+use crate::websockets::connect_websocket;
+
+/// The websocket connection returned by `connect_websocket`.
+pub type ActivitySocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Everything the server replayed before sending `ReplayComplete`.
+#[derive(Debug, Clone, Default)]
+pub struct ActivitySnapshot {
+    /// Latest replayed version of each order, in first-seen order.
+    pub orders: Vec<Order>,
+    /// Every replayed trade, deduplicated by `trade_id`.
+    pub trades: Vec<Trade>,
+    /// Latest replayed position for each symbol, in first-seen order.
+    pub positions: Vec<Position>,
+    /// Server timestamp of the `ReplayComplete` message.
+    pub completed_at: i64,
+}
+
+#[derive(Default)]
+struct SnapshotBuilder {
+    snapshot: ActivitySnapshot,
+    order_index: HashMap<String, usize>,
+    trade_index: HashMap<String, usize>,
+    position_index: HashMap<String, usize>,
+}
+
+impl SnapshotBuilder {
+    fn apply(&mut self, message: ActivityMessage) -> Result<(), Error> {
+        match message {
+            ActivityMessage::SubscribeActivityAck(ack) if !ack.payload.success => {
+                return Err(Error::new(
+                    ErrorType::AuthenticationError,
+                    &format!("Activity subscription rejected: {}", ack.payload.details),
+                ));
+            }
+            ActivityMessage::OrderUpdate(update) => {
+                let order = update.payload.data;
+                match self.order_index.get(&order.order_id) {
+                    Some(&index) => {
+                        if order.version >= self.snapshot.orders[index].version {
+                            self.snapshot.orders[index] = order;
+                        }
+                    }
+                    None => {
+                        self.order_index.insert(order.order_id.clone(), self.snapshot.orders.len());
+                        self.snapshot.orders.push(order);
+                    }
+                }
+            }
+            ActivityMessage::TradeNotice(notice) => {
+                let trade = notice.payload.data;
+                if !self.trade_index.contains_key(&trade.trade_id) {
+                    self.trade_index.insert(trade.trade_id.clone(), self.snapshot.trades.len());
+                    self.snapshot.trades.push(trade);
+                }
+            }
+            ActivityMessage::PositionUpdate(update) => {
+                let position = update.payload.data;
+                match self.position_index.get(&position.symbol) {
+                    Some(&index) => self.snapshot.positions[index] = position,
+                    None => {
+                        self.position_index.insert(position.symbol.clone(), self.snapshot.positions.len());
+                        self.snapshot.positions.push(position);
+                    }
+                }
+            }
+            ActivityMessage::ErrorNotice(notice) => {
+                tracing::warn!("Error notice received during replay: {}", notice.to_error());
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Parsed activity feed on top of a websocket connection.
+///
+/// Yields every incoming message, replayed or live. Call [`ActivityStream::replay`]
+/// first to consume the replay phase as a single [`ActivitySnapshot`].
+pub struct ActivityStream<S = ActivitySocket> {
+    inner: S,
+    replay_complete: bool,
+    latency: Option<LatencyTracker>,
+}
+
+impl<S> ActivityStream<S>
+where
+    S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            replay_complete: false,
+            latency: None,
+        }
+    }
+
+    /// Records receive latency of every live message, and order round trips, into `tracker`.
+    pub fn with_latency_tracker(mut self, tracker: LatencyTracker) -> Self {
+        self.latency = Some(tracker);
+        self
+    }
+
+    /// Whether `ReplayComplete` has been seen, i.e. further messages are live.
+    pub fn is_replay_complete(&self) -> bool {
+        self.replay_complete
+    }
+
+    /// Reads the replay phase up to and including `ReplayComplete`, then hands back
+    /// the stream positioned at the first live message.
+    pub async fn replay(mut self) -> Result<(ActivitySnapshot, Self), Error> {
+        if self.replay_complete {
+            return Err(Error::new(
+                ErrorType::InternalError,
+                "Replay has already completed on this stream",
+            ));
+        }
+
+        let mut builder = SnapshotBuilder::default();
+
+        while let Some(message) = self.next().await {
+            match message? {
+                ActivityMessage::ReplayComplete(complete) => {
+                    builder.snapshot.completed_at = complete.timestamp;
+                    return Ok((builder.snapshot, self));
+                }
+                other => builder.apply(other)?,
+            }
+        }
+
+        Err(Error::new(
+            ErrorType::IoError,
+            "Websocket closed before replay completed",
+        ))
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for ActivityStream<S>
+where
+    S: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    type Item = Result<ActivityMessage, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            let frame = match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(frame))) => frame,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match frame {
+                Message::Text(text) => {
+                    let parsed = parse_message(text);
+
+                    // Replayed messages are historical, their timestamps say nothing about latency.
+                    if let (Some(tracker), true, Ok(message)) = (&this.latency, this.replay_complete, &parsed) {
+                        tracker.record_message(message, Utc::now().timestamp_millis());
+                    }
+
+                    match &parsed {
+                        Ok(ActivityMessage::ReplayComplete(_)) => this.replay_complete = true,
+                        Ok(ActivityMessage::ErrorNotice(notice)) => {
+                            tracing::warn!(category = ?notice.category(), "Error notice received: {}", notice.message());
+                        }
+                        _ => {}
+                    }
+                    return Poll::Ready(Some(parsed));
+                }
+                Message::Close(frame) => {
+                    tracing::debug!("Websocket closed by server: {:?}", frame);
+                    return Poll::Ready(None);
+                }
+                _ => continue,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn connect_activity_stream(client: &AsyncClient) -> Result<ActivityStream, Error> {
+    let ws_stream = connect_websocket(client).await?;
+    Ok(ActivityStream::new(ws_stream))
+}
@@ -0,0 +1,221 @@
+use async_trait::async_trait;
+use clearstreet::authentication::TokenResponse;
+use clearstreet::client::AsyncClearstreetClient;
+use clearstreet::error::{Error, ErrorType};
+use clearstreet::instruments::Instrument;
+use clearstreet::orders::create::{CreateOrderParams, CreateOrderResponse};
+use clearstreet::orders::get::{ListOrdersParams, ListOrdersResponse};
+use clearstreet::orders::update::UpdateOrderRequestBody;
+use clearstreet::orders::{Order, OrderState, OrderStatus};
+use clearstreet::positions::{ListPositionsParams, ListPositionsResponse, Position};
+use clearstreet::trades::{ListTradesParams, ListTradesResponse, Trade};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+fn not_found(what: &str) -> Error {
+    Error::new(ErrorType::NotFound, &format!("{} not found", what))
+}
+
+/// Serves `items` in pages of `page_size`, two by default, with the next offset as the token.
+fn page<T: Clone>(items: &[T], page_size: Option<i64>, page_token: Option<String>) -> (Vec<T>, Option<String>) {
+    let start: usize = page_token.map(|token| token.parse().unwrap()).unwrap_or_default();
+    let end = (start + page_size.unwrap_or(2) as usize).min(items.len());
+    let next = (end < items.len()).then(|| end.to_string());
+    (items[start.min(end)..end].to_vec(), next)
+}
+
+/// In-memory client: created orders are stored and served back, everything else is seeded by the test.
+#[derive(Default)]
+pub struct MockClient {
+    pub orders: Mutex<HashMap<String, Order>>,
+    pub trades: Mutex<Vec<Trade>>,
+    /// Pages served by `list_trades_page`.
+    pub trade_pages: Mutex<usize>,
+    pub positions: Mutex<Vec<Position>>,
+    pub created: Mutex<Vec<CreateOrderParams>>,
+    pub updated: Mutex<Vec<(String, UpdateOrderRequestBody)>>,
+    pub deleted: Mutex<Vec<String>>,
+    /// Symbols whose orders are rejected by `create_order`.
+    pub reject_symbols: Mutex<Vec<String>>,
+    /// Records `update_order` calls without applying them, leaving the outcome to the test.
+    pub hold_updates: Mutex<bool>,
+    /// Leaves `create_order` pending forever, for tests that abandon the call.
+    pub stall_creates: Mutex<bool>,
+    /// Token returned by `fetch_new_token`, which fails while unset.
+    pub token: Mutex<Option<String>>,
+}
+
+impl MockClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_order(&self, order: Order) {
+        self.orders.lock().unwrap().insert(order.order_id.clone(), order);
+    }
+
+    pub fn order(&self, order_id: &str) -> Option<Order> {
+        self.orders.lock().unwrap().get(order_id).cloned()
+    }
+
+    pub fn add_trade(&self, trade: Trade) {
+        self.trades.lock().unwrap().push(trade);
+    }
+
+    pub fn created_count(&self) -> usize {
+        self.created.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl AsyncClearstreetClient for MockClient {
+    fn set_token(&mut self, _token: &str) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_account_id(&self) -> String {
+        "test-account".to_string()
+    }
+
+    async fn fetch_new_token(&self) -> Result<TokenResponse, Error> {
+        match self.token.lock().unwrap().clone() {
+            Some(access_token) => Ok(TokenResponse {
+                access_token,
+                expires_in: 3600,
+            }),
+            None => Err(Error::new(ErrorType::AuthenticationError, "mock client has no token")),
+        }
+    }
+
+    async fn create_order(&self, params: CreateOrderParams) -> Result<CreateOrderResponse, Error> {
+        if *self.stall_creates.lock().unwrap() {
+            std::future::pending::<()>().await;
+        }
+        if self.reject_symbols.lock().unwrap().contains(&params.symbol) {
+            return Err(Error::new(ErrorType::OrderRejected, &format!("{} rejected", params.symbol)));
+        }
+
+        let mut created = self.created.lock().unwrap();
+        let order_id = format!("mock-order-{}", created.len() + 1);
+
+        self.set_order(Order {
+            order_id: order_id.clone(),
+            reference_id: Some(params.reference_id.clone()),
+            version: 1,
+            account_id: params.account_id.clone(),
+            state: OrderState::Open,
+            status: OrderStatus::New,
+            symbol: params.symbol.clone(),
+            order_type: params.order_type.clone(),
+            side: params.order_side.clone(),
+            quantity: params.quantity.clone(),
+            price: params.price.clone(),
+            stop_price: params.stop_price.clone(),
+            time_in_force: params.time_in_force.clone(),
+            filled_quantity: "0".to_string(),
+            strategy: params.strategy.clone(),
+            ..Default::default()
+        });
+        created.push(params);
+
+        Ok(CreateOrderResponse { order_id })
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<Order, Error> {
+        self.order(order_id).ok_or_else(|| not_found(order_id))
+    }
+
+    async fn update_order(&self, order_id: &str, params: UpdateOrderRequestBody) -> Result<(), Error> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.get_mut(order_id).ok_or_else(|| not_found(order_id))?;
+
+        if *self.hold_updates.lock().unwrap() {
+            self.updated.lock().unwrap().push((order_id.to_string(), params));
+            return Ok(());
+        }
+
+        order.version += 1;
+        order.quantity = params.quantity.clone();
+        order.price = params.price.clone();
+        order.stop_price = params.stop_price.clone();
+        self.updated.lock().unwrap().push((order_id.to_string(), params));
+
+        Ok(())
+    }
+
+    async fn delete_order(&self, order_id: &str) -> Result<(), Error> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.get_mut(order_id).ok_or_else(|| not_found(order_id))?;
+
+        order.version += 1;
+        order.state = OrderState::Closed;
+        order.status = OrderStatus::Canceled;
+        self.deleted.lock().unwrap().push(order_id.to_string());
+
+        Ok(())
+    }
+
+    async fn delete_all_orders(&self, symbol: Option<&str>) -> Result<(), Error> {
+        let order_ids: Vec<String> = self
+            .orders
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|o| o.state == OrderState::Open && symbol.is_none_or(|s| o.symbol == s))
+            .map(|o| o.order_id.clone())
+            .collect();
+
+        for order_id in order_ids {
+            self.delete_order(&order_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_orders(&self, _params: ListOrdersParams) -> Result<ListOrdersResponse, Error> {
+        Ok(ListOrdersResponse {
+            data: self.orders.lock().unwrap().values().cloned().collect(),
+            next_page_token: None,
+        })
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Position, Error> {
+        self.positions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.symbol == symbol)
+            .cloned()
+            .ok_or_else(|| not_found(symbol))
+    }
+
+    async fn list_positions(&self) -> Result<ListPositionsResponse, Error> {
+        Ok(ListPositionsResponse {
+            data: self.positions.lock().unwrap().clone(),
+            next_page_token: None,
+        })
+    }
+
+    async fn list_positions_page(&self, params: ListPositionsParams) -> Result<ListPositionsResponse, Error> {
+        let (data, next_page_token) = page(&self.positions.lock().unwrap(), params.page_size, params.page_token);
+        Ok(ListPositionsResponse { data, next_page_token })
+    }
+
+    async fn list_trades_page(&self, params: ListTradesParams) -> Result<ListTradesResponse, Error> {
+        *self.trade_pages.lock().unwrap() += 1;
+        let (data, next_page_token) = page(&self.trades.lock().unwrap(), params.page_size, params.page_token);
+        Ok(ListTradesResponse { data, next_page_token })
+    }
+
+    async fn get_instrument(&self, symbol: &str) -> Result<Instrument, Error> {
+        Err(not_found(symbol))
+    }
+
+    async fn connect_websocket(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, Error> {
+        Err(Error::new(ErrorType::IoError, "mock client has no websocket"))
+    }
+}
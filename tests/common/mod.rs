@@ -0,0 +1,190 @@
+#![allow(dead_code)]
+
+pub mod mock;
+
+use chrono::Utc;
+use clearstreet::client::ClientOptions;
+use futures_util::Stream;
+use tokio::sync::mpsc;
+use tungstenite::Message;
+use clearstreet::orders::create::CreateOrderParams;
+use clearstreet::orders::strategy::{Percent, Strategy};
+use clearstreet::orders::{Order, OrderSide, OrderStatus, OrderType, SymbolFormat, TimeInForce};
+use clearstreet::positions::Position;
+use clearstreet::trades::Trade;
+use clearstreet::websockets::payloads::{
+    Heartbeat, HeartbeatPayload, OrderUpdatePayload, PositionUpdatePayload, ReplayCompletePayload,
+    SubscribeActivityAckPayload, TradeNoticePayload,
+};
+use clearstreet::websockets::{
+    OrderUpdate, PayloadType, PositionUpdate, ReplayComplete, SubscribeActivityAck, TradeNotice,
+};
+
+/// Accepts one blocking websocket session, reads its subscribe message, sends `frames` and closes.
+/// Returns client options pointing at it.
+pub fn scripted_websocket(frames: Vec<String>) -> ClientOptions {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept(stream).unwrap();
+        let _subscribe = socket.read().unwrap();
+        for frame in frames {
+            socket.send(Message::Text(frame.into())).unwrap();
+        }
+        let _ = socket.close(None);
+        let _ = socket.flush();
+        // Drain until the client acknowledges the close.
+        while socket.read().is_ok() {}
+    });
+
+    ClientOptions {
+        websocket_url: format!("ws://{}", address),
+        account_id: "test-account".to_string(),
+        ..Default::default()
+    }
+}
+
+/// A websocket-like frame stream fed by the returned sender; ends when the sender is dropped.
+pub fn frame_channel() -> (
+    mpsc::UnboundedSender<String>,
+    impl Stream<Item = Result<Message, tungstenite::Error>> + Unpin + Send + 'static,
+) {
+    let (sender, receiver) = mpsc::unbounded_channel::<String>();
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        let text = receiver.recv().await?;
+        Some((Ok(Message::Text(text.into())), receiver))
+    });
+    (sender, Box::pin(stream))
+}
+
+pub fn now() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+pub fn percent(value: f64) -> Percent {
+    Percent::new(value).unwrap()
+}
+
+pub fn order(order_id: &str, version: i64, status: OrderStatus) -> Order {
+    Order {
+        order_id: order_id.to_string(),
+        version,
+        status,
+        account_id: "test-account".to_string(),
+        symbol: "AAPL".to_string(),
+        quantity: "100".to_string(),
+        filled_quantity: "0".to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn order_params(symbol: &str, quantity: &str) -> CreateOrderParams {
+    CreateOrderParams {
+        account_id: "test-account".to_string(),
+        reference_id: format!("ref-{}", symbol),
+        order_type: OrderType::Market,
+        order_side: OrderSide::Buy,
+        quantity: quantity.to_string(),
+        price: None,
+        stop_price: None,
+        time_in_force: TimeInForce::Day,
+        symbol: symbol.to_string(),
+        symbol_format: SymbolFormat::Cms,
+        strategy: Strategy::default(),
+    }
+}
+
+pub fn trade(trade_id: &str, order_id: &str, quantity: &str, price: &str) -> Trade {
+    Trade {
+        created_at: now(),
+        account_id: "test-account".to_string(),
+        account_number: "T0001".to_string(),
+        trade_id: trade_id.to_string(),
+        order_id: order_id.to_string(),
+        symbol: "AAPL".to_string(),
+        side: OrderSide::Buy,
+        quantity: quantity.to_string(),
+        price: price.to_string(),
+        running_position: quantity.to_string(),
+    }
+}
+
+pub fn position(symbol: &str, quantity: &str, average_cost: f64) -> Position {
+    Position {
+        account_id: "test-account".to_string(),
+        account_number: "T0001".to_string(),
+        symbol: symbol.to_string(),
+        quantity: quantity.to_string(),
+        average_cost,
+    }
+}
+
+pub fn ack_frame(success: bool) -> String {
+    serde_json::to_string(&SubscribeActivityAck {
+        timestamp: now(),
+        payload: SubscribeActivityAckPayload {
+            payload_type: PayloadType::SubscribeActivityAck,
+            success,
+            details: String::new(),
+        },
+    })
+    .unwrap()
+}
+
+pub fn replay_complete_frame() -> String {
+    serde_json::to_string(&ReplayComplete {
+        timestamp: now(),
+        payload: ReplayCompletePayload {
+            payload_type: PayloadType::ReplayComplete,
+        },
+    })
+    .unwrap()
+}
+
+pub fn heartbeat_frame() -> String {
+    serde_json::to_string(&Heartbeat {
+        timestamp: now(),
+        payload: HeartbeatPayload {
+            payload_type: PayloadType::Heartbeat,
+        },
+    })
+    .unwrap()
+}
+
+pub fn order_update_frame(order: Order, sequence: i64) -> String {
+    serde_json::to_string(&OrderUpdate {
+        timestamp: now(),
+        sequence,
+        payload: OrderUpdatePayload {
+            payload_type: PayloadType::OrderUpdate,
+            data: order,
+        },
+    })
+    .unwrap()
+}
+
+pub fn trade_notice_frame(trade: Trade, sequence: i64) -> String {
+    serde_json::to_string(&TradeNotice {
+        timestamp: now(),
+        sequence,
+        payload: TradeNoticePayload {
+            payload_type: PayloadType::TradeNotice,
+            data: trade,
+        },
+    })
+    .unwrap()
+}
+
+pub fn position_update_frame(position: Position, sequence: i64) -> String {
+    serde_json::to_string(&PositionUpdate {
+        timestamp: now(),
+        sequence,
+        payload: PositionUpdatePayload {
+            payload_type: PayloadType::PositionUpdate,
+            data: position,
+        },
+    })
+    .unwrap()
+}
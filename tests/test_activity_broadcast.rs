@@ -0,0 +1,72 @@
+mod common;
+
+use clearstreet::orders::OrderStatus;
+use clearstreet::websockets::{
+    ActivityBroadcaster, ActivityFilter, ActivityStream, BroadcastOptions, LagPolicy, PayloadType,
+};
+use common::*;
+
+#[tokio::test]
+pub async fn test_fan_out_with_filters() {
+    let (frames, stream) = frame_channel();
+    let broadcaster = ActivityBroadcaster::new(ActivityStream::new(stream), BroadcastOptions::default());
+
+    let mut everything = broadcaster.subscribe(ActivityFilter::all());
+    let mut order_updates = broadcaster.subscribe_order_updates();
+    let mut msft_trades = broadcaster.subscribe_trade_notices(Some("MSFT"));
+    assert_eq!(broadcaster.subscriber_count(), 3);
+
+    let mut msft_trade = trade("trade-2", "order-2", "10", "400.00");
+    msft_trade.symbol = "MSFT".to_string();
+
+    frames.send(heartbeat_frame()).unwrap();
+    frames.send(trade_notice_frame(trade("trade-1", "order-1", "10", "150.00"), 1)).unwrap();
+    frames.send(order_update_frame(order("order-1", 1, OrderStatus::New), 2)).unwrap();
+    frames.send(trade_notice_frame(msft_trade, 3)).unwrap();
+    drop(frames);
+
+    let mut received = Vec::new();
+    while let Some(message) = everything.recv().await {
+        received.push(message.unwrap().payload_type());
+    }
+    assert_eq!(
+        received,
+        vec![
+            PayloadType::Heartbeat,
+            PayloadType::TradeNotice,
+            PayloadType::OrderUpdate,
+            PayloadType::TradeNotice
+        ]
+    );
+
+    let update = order_updates.recv().await.unwrap().unwrap();
+    assert_eq!(update.payload.data.order_id, "order-1");
+    assert!(order_updates.recv().await.is_none());
+
+    let notice = msft_trades.recv().await.unwrap().unwrap();
+    assert_eq!(notice.payload.data.trade_id, "trade-2");
+    assert!(msft_trades.recv().await.is_none());
+}
+
+#[tokio::test]
+pub async fn test_lagging_subscriber_fails() {
+    let (frames, stream) = frame_channel();
+    let options = BroadcastOptions {
+        capacity: 2,
+        lag_policy: LagPolicy::Fail,
+    };
+    let broadcaster = ActivityBroadcaster::new(ActivityStream::new(stream), options);
+    let mut slow = broadcaster.subscribe(ActivityFilter::all());
+
+    for _ in 0..5 {
+        frames.send(heartbeat_frame()).unwrap();
+    }
+    drop(frames);
+
+    while broadcaster.is_connected() {
+        tokio::task::yield_now().await;
+    }
+
+    assert!(slow.recv().await.unwrap().is_err());
+    assert!(slow.recv().await.unwrap().is_ok());
+}
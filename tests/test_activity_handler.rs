@@ -0,0 +1,78 @@
+mod common;
+
+use clearstreet::error::Error;
+use clearstreet::orders::OrderStatus;
+use clearstreet::websockets::{
+    run_activity_handler, ActivityHandler, ActivityStream, Heartbeat, OrderUpdate, ReplayComplete,
+    TradeNotice,
+};
+use common::*;
+
+#[derive(Default)]
+struct RecordingHandler {
+    events: Vec<String>,
+}
+
+impl ActivityHandler for RecordingHandler {
+    fn on_order_update(&mut self, update: &OrderUpdate) {
+        self.events.push(format!("order:{}", update.payload.data.order_id));
+    }
+
+    fn on_trade_notice(&mut self, notice: &TradeNotice) {
+        self.events.push(format!("trade:{}", notice.payload.data.trade_id));
+    }
+
+    fn on_heartbeat(&mut self, _heartbeat: &Heartbeat) {
+        self.events.push("heartbeat".to_string());
+    }
+
+    fn on_replay_complete(&mut self, _complete: &ReplayComplete) {
+        self.events.push("replay-complete".to_string());
+    }
+
+    fn on_disconnect(&mut self, error: Option<&Error>) {
+        self.events.push(format!("disconnect:{}", error.is_some()));
+    }
+}
+
+#[tokio::test]
+pub async fn test_handler_dispatch() {
+    let (frames, stream) = frame_channel();
+
+    frames.send(ack_frame(true)).unwrap();
+    frames.send(replay_complete_frame()).unwrap();
+    frames.send(order_update_frame(order("order-1", 1, OrderStatus::New), 1)).unwrap();
+    frames.send("not json".to_string()).unwrap();
+    frames.send(trade_notice_frame(trade("trade-1", "order-1", "100", "150.00"), 2)).unwrap();
+    frames.send(position_update_frame(position("AAPL", "100", 150.0), 3)).unwrap();
+    frames.send(heartbeat_frame()).unwrap();
+    drop(frames);
+
+    let mut handler = RecordingHandler::default();
+    run_activity_handler(ActivityStream::new(stream), &mut handler)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        handler.events,
+        vec![
+            "replay-complete",
+            "order:order-1",
+            "trade:trade-1",
+            "heartbeat",
+            "disconnect:false"
+        ]
+    );
+}
+
+#[tokio::test]
+pub async fn test_handler_rejected_subscription() {
+    let (frames, stream) = frame_channel();
+    frames.send(ack_frame(false)).unwrap();
+
+    let mut handler = RecordingHandler::default();
+    let result = run_activity_handler(ActivityStream::new(stream), &mut handler).await;
+
+    assert!(result.is_err());
+    assert_eq!(handler.events, vec!["disconnect:true"]);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use clearstreet::error::Error;
+use clearstreet::orders::OrderStatus;
+use clearstreet::testing::{ActivityServer, ActivityServerOptions};
+use clearstreet::websockets::payloads::{parse_message, PayloadType};
+use clearstreet::websockets::{
+    activity_subscription_message, run_activity_handler_blocking, ActivityHandler, OrderUpdate, ReplayComplete,
+};
+use common::*;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Default, Clone)]
+struct SharedHandler {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl SharedHandler {
+    fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn wait_for(&self, event: &str) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !self.events().iter().any(|e| e == event) {
+            assert!(Instant::now() < deadline, "timed out waiting for {}", event);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl ActivityHandler for SharedHandler {
+    fn on_order_update(&mut self, update: &OrderUpdate) {
+        self.events.lock().unwrap().push(format!("order:{}", update.payload.data.order_id));
+    }
+
+    fn on_replay_complete(&mut self, _complete: &ReplayComplete) {
+        self.events.lock().unwrap().push("replay-complete".to_string());
+    }
+
+    fn on_disconnect(&mut self, error: Option<&Error>) {
+        self.events.lock().unwrap().push(format!("disconnect:{}", error.is_some()));
+    }
+}
+
+fn connect_blocking(
+    server: &ActivityServer,
+    token: &str,
+) -> tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>> {
+    let (mut socket, _response) = tungstenite::connect(server.url()).unwrap();
+    socket
+        .send(activity_subscription_message(PayloadType::SubscribeActivity, token, "test-account").unwrap())
+        .unwrap();
+    socket
+}
+
+#[test]
+pub fn test_blocking_handler_against_server() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime
+        .block_on(ActivityServer::start(ActivityServerOptions {
+            replay: vec![order_update_frame(order("order-1", 1, OrderStatus::New), 1)],
+            ..Default::default()
+        }))
+        .unwrap();
+
+    let mut socket = connect_blocking(&server, &server.token());
+    let handler = SharedHandler::default();
+    let mut running = handler.clone();
+    let runner = std::thread::spawn(move || run_activity_handler_blocking(&mut socket, &mut running));
+
+    handler.wait_for("replay-complete");
+    let update = parse_message(order_update_frame(order("order-2", 1, OrderStatus::New), 2).into()).unwrap();
+    server.push(&update).unwrap();
+    handler.wait_for("order:order-2");
+
+    // The server drops the connection without a close handshake, which surfaces as an error.
+    server.disconnect_all();
+    assert!(runner.join().unwrap().is_err());
+    assert_eq!(
+        handler.events(),
+        vec!["order:order-1", "replay-complete", "order:order-2", "disconnect:true"]
+    );
+}
+
+#[test]
+pub fn test_blocking_handler_rejected_subscription() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(ActivityServer::start(ActivityServerOptions::default())).unwrap();
+
+    let mut socket = connect_blocking(&server, "wrong-token");
+    let mut handler = SharedHandler::default();
+    let result = run_activity_handler_blocking(&mut socket, &mut handler);
+
+    assert!(result.is_err());
+    assert_eq!(handler.events(), vec!["disconnect:true"]);
+}
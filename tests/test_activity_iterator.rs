@@ -0,0 +1,85 @@
+mod common;
+
+use clearstreet::error::ErrorType;
+use clearstreet::orders::OrderStatus;
+use clearstreet::websockets::{ActivityIterator, ActivityIteratorOptions, ActivityMessage};
+use common::*;
+use std::time::Duration;
+
+fn options() -> ActivityIteratorOptions {
+    ActivityIteratorOptions {
+        read_timeout: Duration::from_millis(50),
+        reconnect: false,
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn test_iterator_yields_until_close() {
+    let client_options = scripted_websocket(vec![
+        ack_frame(true),
+        order_update_frame(order("order-1", 1, OrderStatus::New), 1),
+        heartbeat_frame(),
+        replay_complete_frame(),
+    ]);
+    let activity = ActivityIterator::connect_with(&client_options, "token", options()).unwrap();
+
+    let messages: Vec<ActivityMessage> = activity.collect::<Result<_, _>>().unwrap();
+    assert_eq!(messages.len(), 3);
+    assert!(matches!(messages[1], ActivityMessage::OrderUpdate(_)));
+}
+
+#[test]
+pub fn test_iterator_rejected_subscription() {
+    let client_options = scripted_websocket(vec![ack_frame(false), replay_complete_frame()]);
+    let options = ActivityIteratorOptions { reconnect: true, ..options() };
+    let mut activity = ActivityIterator::connect_with(&client_options, "token", options).unwrap();
+
+    let error = activity.next().unwrap().unwrap_err();
+    assert_eq!(error.error_type, ErrorType::AuthenticationError);
+    assert!(activity.next().is_none());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+pub fn test_iterator_reconnects_and_shuts_down() {
+    use clearstreet::testing::{ActivityServer, ActivityServerOptions};
+    use clearstreet::websockets::payloads::parse_message;
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime
+        .block_on(ActivityServer::start(ActivityServerOptions {
+            heartbeat_interval: Some(Duration::from_millis(20)),
+            ..Default::default()
+        }))
+        .unwrap();
+
+    let options = ActivityIteratorOptions {
+        read_timeout: Duration::from_millis(50),
+        reconnect_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let mut activity = ActivityIterator::connect_with(&server.client_options("test-account"), &server.token(), options)
+        .unwrap()
+        .refresh_token_with(|| Ok("fresh-token".to_string()));
+    let shutdown = activity.shutdown_handle();
+
+    assert!(matches!(activity.next(), Some(Ok(ActivityMessage::SubscribeActivityAck(_)))));
+    assert!(matches!(activity.next(), Some(Ok(ActivityMessage::ReplayComplete(_)))));
+
+    // The old token expires, so the reconnect has to use a fresh one.
+    server.set_token("fresh-token");
+    server.disconnect_all();
+    assert!(matches!(activity.next(), Some(Ok(ActivityMessage::SubscribeActivityAck(_)))));
+    assert!(matches!(activity.next(), Some(Ok(ActivityMessage::ReplayComplete(_)))));
+    assert_eq!(server.subscriptions().len(), 2);
+    assert_eq!(server.subscriptions()[1].authorization, "fresh-token");
+
+    let update = parse_message(order_update_frame(order("order-1", 1, OrderStatus::New), 1).into()).unwrap();
+    server.push(&update).unwrap();
+    assert!(matches!(activity.next(), Some(Ok(ActivityMessage::OrderUpdate(_)))));
+
+    let stopper = std::thread::spawn(move || shutdown.shutdown());
+    stopper.join().unwrap();
+    assert!(activity.next().is_none());
+}
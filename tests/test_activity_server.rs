@@ -0,0 +1,66 @@
+mod common;
+
+use clearstreet::client::async_client::AsyncClient;
+use clearstreet::client::AsyncClearstreetClient;
+use clearstreet::orders::OrderStatus;
+use clearstreet::testing::{ActivityServer, ActivityServerOptions};
+use clearstreet::websockets::payloads::parse_message;
+use clearstreet::websockets::ActivityMessage;
+use common::*;
+use futures_util::StreamExt;
+use std::time::Duration;
+
+fn client(server: &ActivityServer, token: &str) -> AsyncClient {
+    AsyncClient {
+        client: reqwest::Client::new(),
+        client_options: server.client_options("test-account"),
+        token: token.to_string(),
+    }
+}
+
+#[tokio::test]
+pub async fn test_local_activity_session() {
+    let server = ActivityServer::start(ActivityServerOptions {
+        replay: vec![order_update_frame(order("order-1", 1, OrderStatus::New), 1)],
+        heartbeat_interval: Some(Duration::from_millis(20)),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let options = server.client_options("test-account");
+    assert_eq!(options.api_url.replacen("http", "ws", 1), options.websocket_url);
+
+    let stream = client(&server, &server.token()).connect_activity_stream().await.unwrap();
+    let (snapshot, mut live) = stream.replay().await.unwrap();
+    assert_eq!(snapshot.orders.len(), 1);
+    assert_eq!(server.subscriptions()[0].payload.account_id, "test-account");
+
+    let filled = parse_message(order_update_frame(order("order-1", 2, OrderStatus::Filled), 2).into()).unwrap();
+    server.push(&filled).unwrap();
+
+    loop {
+        match live.next().await.unwrap().unwrap() {
+            ActivityMessage::Heartbeat(_) => continue,
+            ActivityMessage::OrderUpdate(update) => {
+                assert_eq!(update.payload.data.status, OrderStatus::Filled);
+                break;
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    server.disconnect_all();
+    while let Some(Ok(_)) = live.next().await {}
+    while server.connection_count() > 0 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+#[tokio::test]
+pub async fn test_local_activity_session_rejects_bad_token() {
+    let server = ActivityServer::start(ActivityServerOptions::default()).await.unwrap();
+
+    let stream = client(&server, "wrong-token").connect_activity_stream().await.unwrap();
+
+    assert!(stream.replay().await.is_err());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use clearstreet::orders::OrderStatus;
+use clearstreet::websockets::payloads::parse_message;
+use clearstreet::websockets::{ActivityMessage, ActivityStream, LatencyTracker, PayloadType};
+use common::mock::MockClient;
+use common::*;
+use futures_util::{stream, StreamExt};
+use std::time::Duration;
+use tungstenite::Message;
+
+fn frames(texts: Vec<String>) -> impl futures_util::Stream<Item = Result<Message, tungstenite::Error>> + Unpin {
+    stream::iter(texts.into_iter().map(|text| Message::Text(text.into())).map(Ok))
+}
+
+#[tokio::test]
+pub async fn test_replay_snapshot_then_live() {
+    let activity = ActivityStream::new(frames(vec![
+        ack_frame(true),
+        order_update_frame(order("order-1", 1, OrderStatus::New), 1),
+        order_update_frame(order("order-1", 2, OrderStatus::PartiallyFilled), 2),
+        trade_notice_frame(trade("trade-1", "order-1", "50", "150.00"), 3),
+        position_update_frame(position("AAPL", "50", 150.0), 4),
+        replay_complete_frame(),
+        order_update_frame(order("order-1", 3, OrderStatus::Filled), 5),
+    ]));
+
+    let (snapshot, mut live) = activity.replay().await.unwrap();
+
+    assert!(live.is_replay_complete());
+    assert_eq!(snapshot.orders.len(), 1);
+    assert_eq!(snapshot.orders[0].version, 2);
+    assert_eq!(snapshot.trades.len(), 1);
+    assert_eq!(snapshot.positions.len(), 1);
+
+    match live.next().await {
+        Some(Ok(ActivityMessage::OrderUpdate(update))) => {
+            assert_eq!(update.payload.data.status, OrderStatus::Filled);
+        }
+        other => panic!("Unexpected live message: {:?}", other),
+    }
+    assert!(live.next().await.is_none());
+}
+
+#[tokio::test]
+pub async fn test_replay_rejected_subscription() {
+    let activity = ActivityStream::new(frames(vec![ack_frame(false), replay_complete_frame()]));
+
+    assert!(activity.replay().await.is_err());
+}
+
+#[tokio::test]
+pub async fn test_latency_tracked_for_live_messages() {
+    let tracker = LatencyTracker::new(100);
+    tracker.record_order_submitted("order-2", now() - 50);
+
+    let activity = ActivityStream::new(frames(vec![
+        order_update_frame(order("order-1", 1, OrderStatus::New), 1),
+        replay_complete_frame(),
+        order_update_frame(order("order-2", 1, OrderStatus::New), 2),
+        heartbeat_frame(),
+    ]))
+    .with_latency_tracker(tracker.clone());
+
+    let messages: Vec<_> = activity.collect().await;
+    assert_eq!(messages.len(), 4);
+
+    let order_latency = tracker.receive_latency(&PayloadType::OrderUpdate).unwrap();
+    assert_eq!(order_latency.count, 1);
+    assert!(tracker.receive_latency(&PayloadType::Heartbeat).is_some());
+    assert!(tracker.receive_latency(&PayloadType::ReplayComplete).is_none());
+
+    let round_trip = tracker.order_round_trip().unwrap();
+    assert_eq!(round_trip.count, 1);
+    assert!(round_trip.p50_ms >= 50);
+}
+
+#[test]
+pub fn test_latency_ignores_untracked_updates() {
+    let tracker = LatencyTracker::new(100);
+    let update = parse_message(order_update_frame(order("order-3", 1, OrderStatus::New), 1).into()).unwrap();
+
+    // Nothing is in flight, so the update is not held for a later submission.
+    tracker.record_message(&update, now());
+    tracker.record_order_submitted("order-3", now());
+    assert!(tracker.order_round_trip().is_none());
+}
+
+#[tokio::test]
+pub async fn test_latency_forgets_abandoned_submissions() {
+    let tracker = LatencyTracker::new(100);
+    let client = MockClient::new();
+    *client.stall_creates.lock().unwrap() = true;
+
+    let submit = tracker.create_order(&client, order_params("AAPL", "10"));
+    assert!(tokio::time::timeout(Duration::from_millis(10), submit).await.is_err());
+
+    // The dropped call is no longer in flight, so the update is not held.
+    let update = parse_message(order_update_frame(order("order-4", 1, OrderStatus::New), 1).into()).unwrap();
+    tracker.record_message(&update, now());
+    tracker.record_order_submitted("order-4", now());
+    assert!(tracker.order_round_trip().is_none());
+}
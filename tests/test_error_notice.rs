@@ -0,0 +1,39 @@
+use clearstreet::error::{Error, ErrorType};
+use clearstreet::websockets::payloads::parse_message;
+use clearstreet::websockets::{ActivityMessage, ErrorCategory, ErrorNotice};
+
+fn error_notice(details: &str) -> ErrorNotice {
+    let json = serde_json::json!({"timestamp": 1, "payload": {"type": "error-notice", "details": details}});
+    match parse_message(json.to_string().into()).unwrap() {
+        ActivityMessage::ErrorNotice(notice) => notice,
+        other => panic!("Expected an error notice, got {:?}", other),
+    }
+}
+
+#[test]
+pub fn test_error_notice_maps_to_error() {
+    let notice = error_notice("Order rejected: insufficient buying power");
+    assert_eq!(notice.message(), "Order rejected: insufficient buying power");
+    assert_eq!(notice.category(), ErrorCategory::Rejected);
+
+    let error = Error::from(&notice);
+    assert_eq!(error.error_type, ErrorType::OrderRejected);
+    assert_eq!(error.message, "Order rejected: insufficient buying power");
+
+    let plain = error_notice("something odd");
+    assert_eq!(plain.category(), ErrorCategory::Unknown);
+    assert_eq!(plain.to_error().error_type, ErrorType::ThirdPartyError);
+}
+
+#[test]
+pub fn test_error_notice_text_classification() {
+    let category = |details: &str| error_notice(details).category();
+
+    assert_eq!(category("moderate volatility, separate venue"), ErrorCategory::Unknown);
+    assert_eq!(category("Author field is accurate"), ErrorCategory::Unknown);
+    assert_eq!(category("Rate limit exceeded"), ErrorCategory::RateLimit);
+    assert_eq!(category("Unauthorized: invalid token"), ErrorCategory::Authentication);
+    assert_eq!(category("Order rejected by venue"), ErrorCategory::Rejected);
+    assert_eq!(category("missing symbol"), ErrorCategory::Validation);
+    assert_eq!(category("internal server error"), ErrorCategory::Internal);
+}
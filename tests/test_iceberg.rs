@@ -0,0 +1,85 @@
+mod common;
+
+use clearstreet::emulation::iceberg::{Iceberg, IcebergOptions, IcebergStatus};
+use clearstreet::orders::{OrderState, OrderStatus, OrderType};
+use common::mock::MockClient;
+use common::*;
+
+fn limit_params(quantity: &str) -> clearstreet::orders::create::CreateOrderParams {
+    let mut params = order_params("AAPL", quantity);
+    params.order_type = OrderType::Limit;
+    params.price = Some("150".to_string());
+    params
+}
+
+#[tokio::test]
+pub async fn test_iceberg_replenishes_and_reports_progress() {
+    let client = MockClient::new();
+    let mut iceberg = Iceberg::start(&client, limit_params("250"), IcebergOptions::new(100.0)).await.unwrap();
+    assert_eq!(client.created.lock().unwrap()[0].quantity, "100");
+
+    // The first child fills over two trades, then the second is placed.
+    iceberg.apply_trade(&client, &trade("t-1", "mock-order-1", "40", "150")).await.unwrap();
+    assert_eq!(client.created_count(), 1);
+    iceberg.apply_trade(&client, &trade("t-2", "mock-order-1", "60", "149")).await.unwrap();
+    iceberg.apply_trade(&client, &trade("t-2", "mock-order-1", "60", "149")).await.unwrap();
+    assert_eq!(client.created_count(), 2);
+
+    // A fill seen only through OrderUpdate also replenishes, and the last child is the remainder.
+    let mut order = client.order("mock-order-2").unwrap();
+    order.version += 1;
+    order.state = OrderState::Closed;
+    order.status = OrderStatus::Filled;
+    order.filled_quantity = "100".to_string();
+    order.average_price = 150.6;
+    iceberg.apply_order(&client, &order).await.unwrap();
+    assert_eq!(client.created.lock().unwrap()[2].quantity, "50");
+
+    let progress = iceberg.progress();
+    assert_eq!(progress.filled_quantity, 200.0);
+    assert_eq!(progress.remaining_quantity, 50.0);
+    assert_eq!(progress.average_price, Some(150.0));
+    assert_eq!(progress.children, 3);
+
+    iceberg.cancel(&client).await.unwrap();
+    assert_eq!(*iceberg.status(), IcebergStatus::Canceled);
+    assert_eq!(*client.deleted.lock().unwrap(), vec!["mock-order-3".to_string()]);
+}
+
+#[tokio::test]
+pub async fn test_iceberg_randomises_within_bounds() {
+    let client = MockClient::new();
+    let options = IcebergOptions::new(100.0).variance(0.2).lot_size(10.0);
+    let mut iceberg = Iceberg::start(&client, limit_params("10000"), options).await.unwrap();
+
+    for n in 1..=20 {
+        let order_id = format!("mock-order-{}", n);
+        let quantity = client.order(&order_id).unwrap().quantity;
+        let size: f64 = quantity.parse().unwrap();
+        assert!((80.0..=120.0).contains(&size) && size % 10.0 == 0.0, "child size {}", size);
+        iceberg.apply_trade(&client, &trade(&order_id, &order_id, &quantity, "150")).await.unwrap();
+    }
+
+    assert!(Iceberg::start(&client, order_params("AAPL", "100"), IcebergOptions::new(10.0)).await.is_err());
+}
+
+#[tokio::test]
+pub async fn test_iceberg_resync_reads_every_page_and_cancel_failure() {
+    let client = MockClient::new();
+    let mut iceberg = Iceberg::start(&client, limit_params("300"), IcebergOptions::new(100.0)).await.unwrap();
+
+    // Trades for other orders fill the first pages, the child's fills come last.
+    for n in 1..=4 {
+        client.add_trade(trade(&format!("other-{}", n), "other-order", "10", "150"));
+    }
+    client.add_trade(trade("t-1", "mock-order-1", "60", "150"));
+    client.add_trade(trade("t-2", "mock-order-1", "40", "150"));
+    iceberg.resync(&client).await.unwrap();
+    assert_eq!(iceberg.progress().filled_quantity, 100.0);
+    assert_eq!(client.created_count(), 2);
+
+    // The cancel fails, so the iceberg is still working.
+    client.orders.lock().unwrap().remove("mock-order-2");
+    assert!(iceberg.cancel(&client).await.is_err());
+    assert_eq!(*iceberg.status(), IcebergStatus::Working);
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use clearstreet::client::async_client::AsyncClient;
+use clearstreet::error::ErrorType;
+use clearstreet::orders::OrderStatus;
+use clearstreet::testing::{ActivityServer, ActivityServerOptions};
+use clearstreet::websockets::payloads::parse_message;
+use clearstreet::websockets::{
+    AccountEvent, ActivityMessage, MultiAccountSession, SessionEvent, SessionOptions,
+};
+use common::mock::MockClient;
+use common::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn next_activity(session: &mut MultiAccountSession) -> AccountEvent {
+    match session.recv().await.unwrap() {
+        SessionEvent::Activity(event) => event,
+        other => panic!("Unexpected session event: {:?}", other),
+    }
+}
+
+fn token_client(token: &str) -> Arc<MockClient> {
+    let client = MockClient::new();
+    *client.token.lock().unwrap() = Some(token.to_string());
+    Arc::new(client)
+}
+
+fn order_for(account_id: &str, order_id: &str) -> ActivityMessage {
+    let mut order = order(order_id, 1, OrderStatus::New);
+    order.account_id = account_id.to_string();
+    parse_message(order_update_frame(order, 1).into()).unwrap()
+}
+
+#[tokio::test]
+pub async fn test_multi_account_session() {
+    let server = ActivityServer::start(ActivityServerOptions::default()).await.unwrap();
+    let options = SessionOptions {
+        reconnect_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+
+    let mut session = MultiAccountSession::connect_with(
+        &server.client_options("account-a"),
+        &server.token(),
+        token_client(&server.token()),
+        &["account-a"],
+        options,
+    )
+    .await
+    .unwrap();
+
+    let ack = next_activity(&mut session).await;
+    assert!(matches!(ack.message, ActivityMessage::SubscribeActivityAck(_)));
+    assert_eq!(ack.account_id.as_deref(), Some("account-a"));
+    let replayed = next_activity(&mut session).await;
+    assert!(matches!(replayed.message, ActivityMessage::ReplayComplete(_)));
+
+    session.subscribe("account-b").unwrap();
+    assert!(server.wait_for_subscriptions(2, Duration::from_secs(5)).await);
+    assert_eq!(next_activity(&mut session).await.account_id.as_deref(), Some("account-b"));
+    assert_eq!(next_activity(&mut session).await.account_id.as_deref(), Some("account-b"));
+
+    server.push(&order_for("account-b", "order-b")).unwrap();
+    let event = next_activity(&mut session).await;
+    assert_eq!(event.account_id.as_deref(), Some("account-b"));
+    assert_eq!(event.message.order_id(), Some("order-b"));
+
+    session.unsubscribe("account-a").unwrap();
+    assert_eq!(session.accounts(), vec!["account-b".to_string()]);
+    assert!(matches!(session.recv().await, Some(SessionEvent::Reconnected)));
+    assert!(server.wait_for_subscriptions(3, Duration::from_secs(5)).await);
+    assert_eq!(server.subscriptions()[2].payload.account_id, "account-b");
+    assert_eq!(next_activity(&mut session).await.account_id.as_deref(), Some("account-b"));
+    assert_eq!(next_activity(&mut session).await.account_id.as_deref(), Some("account-b"));
+
+    server.disconnect_all();
+    assert!(matches!(session.recv().await, Some(SessionEvent::Disconnected(_))));
+    assert!(matches!(session.recv().await, Some(SessionEvent::Reconnected)));
+
+    assert!(server.wait_for_subscriptions(4, Duration::from_secs(5)).await);
+    let restored = server.subscriptions();
+    assert_eq!(restored[3].payload.account_id, "account-b");
+
+    session.shutdown();
+}
+
+#[tokio::test]
+pub async fn test_rejected_account_is_removed() {
+    let server = ActivityServer::start(ActivityServerOptions {
+        accounts: vec!["account-a".to_string()],
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let client = AsyncClient {
+        client: reqwest::Client::new(),
+        client_options: server.client_options("account-a"),
+        token: server.token(),
+    };
+
+    let mut session = MultiAccountSession::connect(&client, &["account-a"], SessionOptions::default())
+        .await
+        .unwrap();
+    next_activity(&mut session).await;
+    next_activity(&mut session).await;
+
+    session.subscribe("account-x").unwrap();
+    let rejected = next_activity(&mut session).await;
+    assert_eq!(rejected.account_id.as_deref(), Some("account-x"));
+    assert!(matches!(&rejected.message, ActivityMessage::SubscribeActivityAck(ack) if !ack.payload.success));
+    assert_eq!(session.accounts(), vec!["account-a".to_string()]);
+
+    session.shutdown();
+}
+
+#[tokio::test]
+pub async fn test_reconnect_fetches_a_fresh_token() {
+    let server = ActivityServer::start(ActivityServerOptions::default()).await.unwrap();
+    let tokens = token_client("fresh-token");
+    let options = SessionOptions {
+        reconnect_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+
+    let mut session = MultiAccountSession::connect_with(
+        &server.client_options("account-a"),
+        &server.token(),
+        tokens.clone(),
+        &["account-a"],
+        options,
+    )
+    .await
+    .unwrap();
+    next_activity(&mut session).await;
+    next_activity(&mut session).await;
+
+    // The old token expires, so the reconnect subscribes with a fresh one.
+    server.set_token("fresh-token");
+    server.disconnect_all();
+    assert!(matches!(session.recv().await, Some(SessionEvent::Disconnected(_))));
+    assert!(matches!(session.recv().await, Some(SessionEvent::Reconnected)));
+    let ack = next_activity(&mut session).await;
+    assert!(matches!(&ack.message, ActivityMessage::SubscribeActivityAck(ack) if ack.payload.success));
+    assert!(ack.error.is_none());
+    assert!(matches!(next_activity(&mut session).await.message, ActivityMessage::ReplayComplete(_)));
+    assert_eq!(server.subscriptions()[1].authorization, "fresh-token");
+
+    // A rejected resubscription is reported and the account is kept.
+    server.set_token("newer-token");
+    server.disconnect_all();
+    assert!(matches!(session.recv().await, Some(SessionEvent::Disconnected(_))));
+    assert!(matches!(session.recv().await, Some(SessionEvent::Reconnected)));
+    let rejected = next_activity(&mut session).await;
+    assert_eq!(rejected.account_id.as_deref(), Some("account-a"));
+    assert!(matches!(&rejected.message, ActivityMessage::SubscribeActivityAck(ack) if !ack.payload.success));
+    assert_eq!(rejected.error.unwrap().error_type, ErrorType::AuthenticationError);
+    assert_eq!(session.accounts(), vec!["account-a".to_string()]);
+
+    // Once a valid token is available the account is subscribed again.
+    *tokens.token.lock().unwrap() = Some("newer-token".to_string());
+    loop {
+        match session.recv().await.unwrap() {
+            SessionEvent::Activity(event) => {
+                if let ActivityMessage::SubscribeActivityAck(ack) = &event.message {
+                    if ack.payload.success {
+                        break;
+                    }
+                }
+            }
+            SessionEvent::Disconnected(_) | SessionEvent::Reconnected => {}
+        }
+    }
+    assert_eq!(session.accounts(), vec!["account-a".to_string()]);
+    assert_eq!(server.subscriptions().last().unwrap().authorization, "newer-token");
+
+    session.shutdown();
+}
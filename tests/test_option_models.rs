@@ -0,0 +1,150 @@
+mod common;
+
+use chrono::NaiveDate;
+use clearstreet::error::ErrorType;
+use clearstreet::instruments::option_symbol::{OptionSymbol, OptionType};
+use clearstreet::instruments::{AssetClass, Instrument};
+use clearstreet::orders::spread::{create_spread_order, SpreadLeg, SpreadOrder};
+use clearstreet::orders::{OrderSide, OrderState, OrderType};
+use clearstreet::positions::option_events::{OptionEventDetector, OptionEventKind};
+use clearstreet::positions::tracker::PositionTracker;
+use common::mock::MockClient;
+use common::*;
+
+fn contract(option_type: OptionType, strike: &str) -> OptionSymbol {
+    OptionSymbol::new("AAPL", NaiveDate::from_ymd_opt(2024, 1, 19).unwrap(), option_type, strike).unwrap()
+}
+
+#[test]
+pub fn test_option_instruments_and_positions() {
+    let json = r#"{
+        "symbols": [{"symbol": "AAPL  240119C00150000", "symbol_format": "osi"}],
+        "asset_class": "option",
+        "primary_exchange": "XCBO",
+        "description": "AAPL Jan 19 2024 150 Call"
+    }"#;
+    let instrument: Instrument = serde_json::from_str(json).unwrap();
+    assert!(instrument.is_option());
+    assert_eq!(instrument.contract_multiplier(), 100.0);
+    assert_eq!(instrument.underlying().as_deref(), Some("AAPL"));
+    assert_eq!(instrument.option_symbol().unwrap().strike(), "150");
+
+    let future: AssetClass = serde_json::from_str(r#""future""#).unwrap();
+    assert_eq!(future, AssetClass::Unknown("future".to_string()));
+    assert_eq!(serde_json::to_string(&future).unwrap(), r#""future""#);
+
+    let option = position("AAPL  240119C00150000", "-3", 2.5);
+    assert!(option.is_option());
+    assert_eq!(option.underlying_quantity(&instrument).unwrap(), -300.0);
+    assert!(!position("AAPL", "10", 150.0).is_option());
+    assert!(position("AAPL", "ten", 150.0).quantity_f64().is_err());
+
+    // Option symbols use the standard multiplier until the instrument is known.
+    let mut tracker = PositionTracker::new();
+    tracker.apply_position(&option).unwrap();
+    tracker.apply_position(&position("AAPL", "-1", 150.0)).unwrap();
+    assert_eq!(tracker.get("AAPL  240119C00150000").unwrap().multiplier, 100.0);
+    assert_eq!(tracker.get("AAPL").unwrap().multiplier, 1.0);
+    assert_eq!(tracker.exposure().short, 900.0);
+    tracker.set_instrument(&instrument);
+    assert_eq!(tracker.exposure().short, 900.0);
+
+    // The instrument's multiplier applies to positions already held.
+
+    let mini: Instrument = serde_json::from_str(&json.replace(r#""option","#, r#""option", "multiplier": 10,"#)).unwrap();
+    tracker.set_instrument(&mini);
+    assert_eq!(tracker.get("AAPL  240119C00150000").unwrap().multiplier, 10.0);
+    assert_eq!(tracker.exposure().short, 225.0);
+}
+
+#[tokio::test]
+pub async fn test_spread_legs_are_placed_together() {
+    let client = MockClient::new();
+    let long_call = contract(OptionType::Call, "150");
+    let short_call = contract(OptionType::Call, "160");
+
+    let spread = SpreadOrder::new("test-account", "vertical", 2)
+        .leg(SpreadLeg::option(&long_call, OrderSide::Buy, 1).limit("3.10"))
+        .leg(SpreadLeg::option(&short_call, OrderSide::Sell, 1));
+    let placed = create_spread_order(&client, &spread).await.unwrap();
+    assert_eq!(placed.len(), 2);
+
+    let mut legs = client.created.lock().unwrap().clone();
+    legs.sort_by(|a, b| a.reference_id.cmp(&b.reference_id));
+    let legs: Vec<_> = legs
+        .iter()
+        .map(|leg| (leg.reference_id.as_str(), leg.symbol.clone(), leg.order_type.clone(), leg.quantity.as_str()))
+        .collect();
+    assert_eq!(
+        legs,
+        vec![
+            ("vertical-1", long_call.to_osi(), OrderType::Limit, "2"),
+            ("vertical-2", short_call.to_osi(), OrderType::Market, "2"),
+        ]
+    );
+
+    // A rejected leg cancels the legs already placed.
+    client.reject_symbols.lock().unwrap().push("AAPL".to_string());
+    let covered_call = SpreadOrder::new("test-account", "covered", 1)
+        .leg(SpreadLeg::option(&short_call, OrderSide::Sell, 1))
+        .leg(SpreadLeg::equity("AAPL", OrderSide::Buy, 100));
+    let error = create_spread_order(&client, &covered_call).await.unwrap_err();
+    assert_eq!(error.error_type, ErrorType::OrderRejected);
+    assert!(error.message.contains("Leg 2"));
+    assert_eq!(client.deleted.lock().unwrap().clone(), vec!["mock-order-3".to_string()]);
+    assert_eq!(client.order("mock-order-3").unwrap().state, OrderState::Closed);
+
+    let empty = SpreadOrder::new("test-account", "empty", 1);
+    assert_eq!(create_spread_order(&client, &empty).await.unwrap_err().error_type, ErrorType::ValidationError);
+}
+
+#[test]
+pub fn test_exercise_assignment_and_expiration() {
+    let call = contract(OptionType::Call, "150");
+    let put = contract(OptionType::Put, "140");
+    let mut tracker = PositionTracker::new();
+    let mut detector = OptionEventDetector::new();
+
+    for position in [position(&call.to_osi(), "2", 3.0), position(&put.to_osi(), "-1", 2.0), position("AAPL", "0", 0.0)] {
+        let change = tracker.apply_position(&position).unwrap().unwrap();
+        assert!(detector.apply_change(&change).is_empty());
+    }
+
+    // Closing one call with a fill is not an exercise.
+    let mut close = trade("trade-1", "order-1", "1", "4.00");
+    close.symbol = call.to_osi();
+    close.side = OrderSide::Sell;
+    detector.apply_trade(&close).unwrap();
+    let change = tracker.apply_position(&position(&call.to_osi(), "1", 3.0)).unwrap().unwrap();
+    assert!(detector.apply_change(&change).is_empty());
+
+    // The remaining call is exercised, the shares may arrive first.
+    let change = tracker.apply_position(&position("AAPL", "100", 150.0)).unwrap().unwrap();
+    assert!(detector.apply_change(&change).is_empty());
+    let change = tracker.apply_position(&position(&call.to_osi(), "0", 0.0)).unwrap().unwrap();
+    let events = detector.apply_change(&change);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, OptionEventKind::Exercise);
+    assert_eq!(events[0].contracts, 1.0);
+    assert_eq!(events[0].underlying_quantity, 100.0);
+
+    // The short put expires without shares being delivered.
+    let change = tracker.apply_position(&position(&put.to_osi(), "0", 0.0)).unwrap().unwrap();
+    assert!(detector.apply_change(&change).is_empty());
+    assert!(detector.expire(NaiveDate::from_ymd_opt(2024, 1, 18).unwrap()).is_empty());
+    let events = detector.expire(put.expiry());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, OptionEventKind::Expiration);
+    assert_eq!(events[0].contract, put);
+
+    // A short put assigned early delivers shares.
+    let change = tracker.apply_position(&position(&put.to_osi(), "-1", 2.0)).unwrap().unwrap();
+    assert!(detector.apply_change(&change).is_empty());
+    let change = tracker.apply_position(&position(&put.to_osi(), "0", 0.0)).unwrap().unwrap();
+    assert!(detector.apply_change(&change).is_empty());
+    let change = tracker.apply_position(&position("AAPL", "200", 145.0)).unwrap().unwrap();
+    let events = detector.apply_change(&change);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, OptionEventKind::Assignment);
+    assert_eq!(events[0].underlying_quantity, 100.0);
+}
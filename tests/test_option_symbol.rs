@@ -0,0 +1,33 @@
+use chrono::NaiveDate;
+use clearstreet::instruments::option_symbol::{OptionSymbol, OptionType};
+use clearstreet::orders::create::CreateOrderParams;
+use clearstreet::orders::validation::validate_order;
+use clearstreet::orders::{OrderSide, SymbolFormat};
+
+#[test]
+pub fn test_osi_round_trip() {
+    let expiry = NaiveDate::from_ymd_opt(2024, 1, 19).unwrap();
+    let call = OptionSymbol::new("aapl", expiry, OptionType::Call, "150").unwrap();
+    assert_eq!(call.to_osi(), "AAPL  240119C00150000");
+    assert_eq!("AAPL  240119C00150000".parse::<OptionSymbol>().unwrap(), call);
+    assert_eq!(OptionSymbol::from_osi("AAPL240119C00150000").unwrap(), call);
+
+    let put = OptionSymbol::from_osi("SPXW  240315P04512500").unwrap();
+    assert_eq!(put.option_type(), OptionType::Put);
+    assert_eq!(put.strike(), "4512.5");
+    assert_eq!(OptionSymbol::from_osi(&put.to_osi()).unwrap(), put);
+
+    assert!(OptionSymbol::new("AAPL", expiry, OptionType::Call, "150.0005").is_err());
+    assert!(OptionSymbol::new("TOOLONG", expiry, OptionType::Call, "150").is_err());
+    assert!(OptionSymbol::from_osi("AAPL 240119C00150000").is_err());
+    assert!(OptionSymbol::from_osi("AAPL  241319C00150000").is_err());
+
+    let order = CreateOrderParams::option_limit("test-account", "ref-1", &put, OrderSide::Buy, 2, "12.30");
+    assert_eq!(order.symbol_format, SymbolFormat::Osi);
+    assert_eq!(order.option_symbol(), Some(put));
+    assert!(validate_order(&order).is_ok());
+
+    // Only OSI symbols are recognised as options.
+    let equity = CreateOrderParams { symbol: "AAPL 240119C150".to_string(), symbol_format: SymbolFormat::Cms, ..order };
+    assert_eq!(equity.option_symbol(), None);
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use clearstreet::client::AsyncClearstreetClient;
+use clearstreet::error::ErrorType;
+use clearstreet::orders::batch::create_orders;
+use clearstreet::orders::OrderState;
+use common::mock::MockClient;
+use common::*;
+
+#[tokio::test]
+pub async fn test_batch_results_in_input_order() {
+    let client = MockClient::new();
+    client.reject_symbols.lock().unwrap().push("BAD".to_string());
+
+    let symbols: Vec<String> = (0..50).map(|i| if i == 7 { "BAD".to_string() } else { format!("SYM{}", i) }).collect();
+    let results = create_orders(&client, symbols.iter().map(|s| order_params(s, "10")).collect(), 4).await;
+
+    assert_eq!(results.len(), 50);
+    assert_eq!(results[7].as_ref().unwrap_err().error_type, ErrorType::OrderRejected);
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 49);
+
+    for (symbol, result) in symbols.iter().zip(&results) {
+        if let Ok(response) = result {
+            assert_eq!(&client.order(&response.order_id).unwrap().symbol, symbol);
+        }
+    }
+
+    let mut order_ids: Vec<String> = results.into_iter().flatten().map(|r| r.order_id).collect();
+    order_ids.push("missing".to_string());
+
+    let canceled = client.cancel_orders(order_ids).await;
+    assert_eq!(canceled.len(), 50);
+    assert!(canceled[..49].iter().all(|r| r.is_ok()));
+    assert_eq!(canceled[49].as_ref().unwrap_err().error_type, ErrorType::NotFound);
+    assert!(client.order("mock-order-1").unwrap().state == OrderState::Closed);
+}
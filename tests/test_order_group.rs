@@ -0,0 +1,177 @@
+mod common;
+
+use clearstreet::emulation::group::{GroupStatus, OrderGroupManager};
+use clearstreet::orders::{OrderSide, OrderState, OrderStatus, OrderType};
+use common::mock::MockClient;
+use common::*;
+
+fn exit_params(order_type: OrderType, price: &str) -> clearstreet::orders::create::CreateOrderParams {
+    let mut params = order_params("AAPL", "100");
+    params.order_side = OrderSide::Sell;
+    params.order_type = order_type.clone();
+    match order_type {
+        OrderType::Stop => params.stop_price = Some(price.to_string()),
+        _ => params.price = Some(price.to_string()),
+    }
+    params
+}
+
+fn close_order(client: &MockClient, order_id: &str, status: OrderStatus, filled: &str) -> clearstreet::orders::Order {
+    let mut order = client.order(order_id).unwrap();
+    order.version += 1;
+    order.state = OrderState::Closed;
+    order.status = status;
+    order.filled_quantity = filled.to_string();
+    client.set_order(order.clone());
+    order
+}
+
+#[tokio::test]
+pub async fn test_bracket_places_exits_on_fill_and_cancels_the_other() {
+    let client = MockClient::new();
+    let mut groups = OrderGroupManager::new();
+    let mut updates = groups.subscribe();
+
+    let mut entry = order_params("AAPL", "100");
+    entry.order_type = OrderType::Limit;
+    entry.price = Some("150".to_string());
+
+    let group_id = groups
+        .submit_bracket(&client, entry, exit_params(OrderType::Limit, "160"), exit_params(OrderType::Stop, "145"))
+        .await
+        .unwrap();
+    assert_eq!(groups.status(&group_id), Some(&GroupStatus::Pending));
+    assert_eq!(client.created_count(), 1);
+
+    // The entry is canceled after a partial fill, so the exits cover the 60 filled.
+    let entry = close_order(&client, "mock-order-1", OrderStatus::Canceled, "60");
+    let update = groups.apply_order(&client, &entry).await.unwrap();
+    assert_eq!(update.current, GroupStatus::Active);
+    assert!(client.created.lock().unwrap()[1..].iter().all(|params| params.quantity == "60"));
+
+    let take_profit = close_order(&client, "mock-order-2", OrderStatus::Filled, "60");
+    assert!(groups.apply_order(&client, &take_profit).await.is_none());
+    assert_eq!(*client.deleted.lock().unwrap(), vec!["mock-order-3".to_string()]);
+
+    // The stop's cancel confirmation was missed, so it is picked up by a resync.
+    let resynced = groups.resync(&client).await.unwrap();
+    assert_eq!(resynced[0].current, GroupStatus::Completed);
+    assert_eq!(updates.try_recv().unwrap().current, GroupStatus::Active);
+    assert_eq!(updates.try_recv().unwrap().current, GroupStatus::Completed);
+}
+
+#[tokio::test]
+pub async fn test_oco_cancel_and_rejection() {
+    let client = MockClient::new();
+    let mut groups = OrderGroupManager::new();
+
+    let group_id = groups
+        .submit_oco(&client, exit_params(OrderType::Limit, "160"), exit_params(OrderType::Stop, "145"))
+        .await
+        .unwrap();
+    assert_eq!(groups.status(&group_id), Some(&GroupStatus::Active));
+
+    groups.cancel(&client, &group_id).await.unwrap();
+    assert_eq!(groups.status(&group_id), Some(&GroupStatus::Canceled));
+    assert_eq!(client.deleted.lock().unwrap().len(), 2);
+
+    let mut entry = order_params("TSLA", "10");
+    entry.order_type = OrderType::Limit;
+    entry.price = Some("200".to_string());
+    let group_id = groups
+        .submit_oto(&client, entry, exit_params(OrderType::Limit, "210"))
+        .await
+        .unwrap();
+
+    let mut rejected = client.order("mock-order-3").unwrap();
+    rejected.version += 1;
+    rejected.state = OrderState::Rejected;
+    rejected.status = OrderStatus::Rejected;
+    groups.apply_order(&client, &rejected).await.unwrap();
+    assert!(matches!(groups.status(&group_id), Some(GroupStatus::Failed(_))));
+    assert_eq!(client.created_count(), 3);
+}
+
+fn fill_order(client: &MockClient, order_id: &str, filled: &str) -> clearstreet::orders::Order {
+    let mut order = client.order(order_id).unwrap();
+    order.version += 1;
+    order.status = OrderStatus::PartiallyFilled;
+    order.filled_quantity = filled.to_string();
+    client.set_order(order.clone());
+    order
+}
+
+#[tokio::test]
+pub async fn test_bracket_follows_partial_fills() {
+    let client = MockClient::new();
+    let mut groups = OrderGroupManager::new();
+
+    let mut entry = order_params("AAPL", "100");
+    entry.order_type = OrderType::Limit;
+    entry.price = Some("150".to_string());
+    let group_id = groups
+        .submit_bracket(&client, entry, exit_params(OrderType::Limit, "160"), exit_params(OrderType::Stop, "145"))
+        .await
+        .unwrap();
+
+    // Exits are placed for the first fill and grow with the entry.
+    let entry = fill_order(&client, "mock-order-1", "30");
+    assert_eq!(groups.apply_order(&client, &entry).await.unwrap().current, GroupStatus::Active);
+    assert!(client.created.lock().unwrap()[1..].iter().all(|params| params.quantity == "30"));
+
+    let entry = close_order(&client, "mock-order-1", OrderStatus::Filled, "100");
+    groups.apply_order(&client, &entry).await;
+    let resized: Vec<_> = client
+        .updated
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(order_id, params)| (order_id.clone(), params.quantity.clone()))
+        .collect();
+    assert_eq!(
+        resized,
+        vec![("mock-order-2".to_string(), "100".to_string()), ("mock-order-3".to_string(), "100".to_string())]
+    );
+    assert_eq!(client.order("mock-order-3").unwrap().stop_price.as_deref(), Some("145"));
+
+    // A partial fill on the take-profit shrinks the stop to the remainder instead of canceling it.
+    let take_profit = fill_order(&client, "mock-order-2", "40");
+    groups.apply_order(&client, &take_profit).await;
+    assert!(client.deleted.lock().unwrap().is_empty());
+    let (order_id, resize) = client.updated.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(order_id, "mock-order-3");
+    assert_eq!((resize.quantity.as_str(), resize.stop_price.as_deref()), ("60", Some("145")));
+    assert_eq!(groups.status(&group_id), Some(&GroupStatus::Active));
+
+    // The stop is canceled once the take-profit is done.
+    let take_profit = close_order(&client, "mock-order-2", OrderStatus::Filled, "100");
+    groups.apply_order(&client, &take_profit).await;
+    assert_eq!(*client.deleted.lock().unwrap(), vec!["mock-order-3".to_string()]);
+
+    let stop = client.order("mock-order-3").unwrap();
+    assert_eq!(groups.apply_order(&client, &stop).await.unwrap().current, GroupStatus::Completed);
+}
+
+#[tokio::test]
+pub async fn test_exit_done_cancels_entry_remainder() {
+    let client = MockClient::new();
+    let mut groups = OrderGroupManager::new();
+
+    let mut entry = order_params("AAPL", "100");
+    entry.order_type = OrderType::Limit;
+    entry.price = Some("150".to_string());
+    let group_id = groups
+        .submit_oto(&client, entry, exit_params(OrderType::Limit, "160"))
+        .await
+        .unwrap();
+
+    let entry = fill_order(&client, "mock-order-1", "50");
+    groups.apply_order(&client, &entry).await;
+    let exit = close_order(&client, "mock-order-2", OrderStatus::Filled, "50");
+    groups.apply_order(&client, &exit).await;
+    assert_eq!(*client.deleted.lock().unwrap(), vec!["mock-order-1".to_string()]);
+
+    let entry = client.order("mock-order-1").unwrap();
+    assert_eq!(groups.apply_order(&client, &entry).await.unwrap().current, GroupStatus::Completed);
+    assert_eq!(groups.status(&group_id), Some(&GroupStatus::Completed));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use clearstreet::error::ErrorType;
+use clearstreet::orders::replace::replace_order;
+use clearstreet::orders::update::UpdateOrderRequestBody;
+use clearstreet::orders::{OrderState, OrderStatus};
+use clearstreet::websockets::{ActivityBroadcaster, ActivityStream, BroadcastOptions};
+use common::mock::MockClient;
+use common::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn reprice(price: &str) -> UpdateOrderRequestBody {
+    UpdateOrderRequestBody {
+        quantity: "100".to_string(),
+        price: Some(price.to_string()),
+        stop_price: None,
+    }
+}
+
+#[tokio::test]
+pub async fn test_replace_order_checks_version() {
+    let client = MockClient::new();
+    client.set_order(order("order-1", 3, OrderStatus::New));
+
+    // Terms are compared by value, "101" is applied as "101.00".
+    let replaced = replace_order(&client, None, "order-1", 3, reprice("101"), Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(replaced.version, 4);
+    let mut normalized = replaced.clone();
+    normalized.version = 5;
+    normalized.price = Some("101.00".to_string());
+    client.set_order(normalized);
+    let replaced = replace_order(&client, None, "order-1", 5, reprice("101.0"), Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(replaced.version, 6);
+
+    // Our view is one version behind, nothing is sent.
+    let error = replace_order(&client, None, "order-1", 5, reprice("102.00"), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::VersionConflict);
+    assert_eq!(client.updated.lock().unwrap().len(), 2);
+
+    client.set_order(order("order-2", 1, OrderStatus::PendingCancel));
+    let error = replace_order(&client, None, "order-2", 1, reprice("102.00"), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidStateTransition);
+}
+
+#[tokio::test]
+pub async fn test_replace_order_outcomes() {
+    let client = Arc::new(MockClient::new());
+    *client.hold_updates.lock().unwrap() = true;
+    client.set_order(order("order-1", 1, OrderStatus::New));
+
+    // A partial fill with the old terms is not the outcome, the later fill closes the order.
+    let background = client.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut partial = order("order-1", 2, OrderStatus::PartiallyFilled);
+        partial.filled_quantity = "40".to_string();
+        background.set_order(partial);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut filled = order("order-1", 3, OrderStatus::Filled);
+        filled.state = OrderState::Closed;
+        filled.filled_quantity = "100".to_string();
+        background.set_order(filled);
+    });
+
+    let error = replace_order(client.as_ref(), None, "order-1", 1, reprice("101.00"), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::OrderClosed);
+
+    // Error notices do not name an order, so only the order's own updates reject the replace.
+    client.set_order(order("order-2", 1, OrderStatus::New));
+    let (frames, stream) = frame_channel();
+    let feed = ActivityBroadcaster::new(ActivityStream::new(stream), BroadcastOptions::default());
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        frames
+            .send(r#"{"timestamp":1,"payload":{"type":"error-notice","details":"Order rejected by venue"}}"#.to_string())
+            .unwrap();
+        frames.send(order_update_frame(order("order-2", 2, OrderStatus::PendingReplace), 1)).unwrap();
+        let mut rejected = order("order-2", 3, OrderStatus::New);
+        rejected.text = "price out of band".to_string();
+        frames.send(order_update_frame(rejected, 2)).unwrap();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    });
+
+    let error = replace_order(client.as_ref(), Some(&feed), "order-2", 1, reprice("101.00"), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::OrderRejected);
+    assert!(error.message.contains("price out of band"));
+
+    // A reject that restores the old terms before PendingReplace is polled is found after the timeout.
+    client.set_order(order("order-3", 1, OrderStatus::New));
+    let background = client.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let mut rejected = order("order-3", 2, OrderStatus::New);
+        rejected.text = "price out of band".to_string();
+        background.set_order(rejected);
+    });
+
+    let error = replace_order(client.as_ref(), None, "order-3", 1, reprice("101.00"), Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::OrderRejected);
+    assert!(error.message.contains("price out of band"));
+}
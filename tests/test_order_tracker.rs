@@ -0,0 +1,94 @@
+mod common;
+
+use clearstreet::error::ErrorType;
+use clearstreet::orders::tracker::{OrderChange, OrderTracker};
+use clearstreet::orders::{OrderState, OrderStatus};
+use common::*;
+
+#[test]
+pub fn test_order_tracker_applies_versions_and_indexes() {
+    let mut tracker = OrderTracker::new();
+    let mut changes = tracker.subscribe();
+
+    let mut first = order("order-1", 1, OrderStatus::New);
+    first.reference_id = Some("ref-1".to_string());
+    let mut second = order("order-2", 1, OrderStatus::New);
+    second.symbol = "MSFT".to_string();
+
+    assert!(matches!(tracker.apply(first.clone()), Some(OrderChange::Added(_))));
+    assert!(tracker.apply(second).is_some());
+
+    let mut filled = first.clone();
+    filled.version = 3;
+    filled.status = OrderStatus::Filled;
+    filled.state = OrderState::Closed;
+    assert!(matches!(tracker.apply(filled), Some(OrderChange::Updated { .. })));
+
+    // Stale and duplicate versions are discarded.
+    let mut stale = first.clone();
+    stale.version = 2;
+    stale.status = OrderStatus::PartiallyFilled;
+    assert!(tracker.apply(stale).is_none());
+    assert!(tracker.apply(first).is_none());
+
+    assert_eq!(tracker.len(), 2);
+    assert_eq!(tracker.get_by_reference_id("ref-1").unwrap().status, OrderStatus::Filled);
+    assert_eq!(tracker.orders_for_symbol("MSFT").len(), 1);
+    assert_eq!(tracker.open_orders().len(), 1);
+    assert_eq!(tracker.orders_by_status(OrderStatus::Filled).len(), 1);
+
+    let mut received = 0;
+    while changes.try_recv().is_ok() {
+        received += 1;
+    }
+    assert_eq!(received, 3);
+}
+
+#[test]
+pub fn test_order_tracker_flags_illegal_transitions() {
+    let mut tracker = OrderTracker::new();
+
+    let mut filled = order("order-1", 1, OrderStatus::Filled);
+    filled.state = OrderState::Closed;
+    assert!(filled.is_terminal());
+    assert!(!filled.is_cancelable());
+    tracker.apply(filled);
+
+    let reopened = order("order-1", 2, OrderStatus::New);
+    let error = tracker.try_apply(reopened.clone()).unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidStateTransition);
+    assert_eq!(tracker.get("order-1").unwrap().version, 1);
+
+    // Plain apply keeps the broker's view but records the violation.
+    assert!(tracker.apply(reopened).is_some());
+    assert_eq!(tracker.invalid_transitions().len(), 1);
+    assert_eq!(tracker.invalid_transitions()[0].to, (OrderState::Open, OrderStatus::New));
+    assert_eq!(tracker.take_invalid_transitions().len(), 1);
+    assert!(tracker.invalid_transitions().is_empty());
+
+    let mut resting = order("order-3", 1, OrderStatus::DoneForDay);
+    assert!(!resting.is_terminal() && resting.is_cancelable());
+    resting.state = OrderState::Unknown("suspended".to_string());
+    assert!(!resting.is_terminal());
+
+    // An unrecognised state is still open.
+    tracker.apply(resting);
+    let mut canceled = order("order-4", 1, OrderStatus::Canceled);
+    canceled.state = OrderState::Closed;
+    tracker.apply(canceled);
+    let mut open: Vec<&str> = tracker.open_orders().iter().map(|order| order.order_id.as_str()).collect();
+    open.sort();
+    assert_eq!(open, vec!["order-1", "order-3"]);
+
+    let working = order("order-2", 1, OrderStatus::PartiallyFilled);
+    assert!(working.is_working() && working.can_replace());
+    assert!(!OrderStatus::PendingReplace.can_replace());
+    assert!(!OrderStatus::PartiallyFilled.can_transition_to(&OrderStatus::New));
+    assert!(OrderStatus::PendingCancel.can_transition_to(&OrderStatus::Canceled));
+
+    let unknown = OrderStatus::Unknown("pending-review".to_string());
+    assert!(!unknown.is_cancelable());
+    assert!(unknown.can_transition_to(&unknown));
+    assert!(!unknown.can_transition_to(&OrderStatus::New));
+    assert!(!OrderStatus::New.can_transition_to(&unknown));
+}
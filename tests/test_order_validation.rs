@@ -0,0 +1,73 @@
+mod common;
+
+use clearstreet::error::ErrorType;
+use clearstreet::orders::strategy::Strategy;
+use clearstreet::orders::validation::validate_order;
+use clearstreet::orders::{OrderType, TimeInForce};
+use std::str::FromStr;
+use common::*;
+
+#[test]
+pub fn test_validate_order_combinations() {
+    let mut limit = order_params("AAPL", "100");
+    limit.order_type = OrderType::Limit;
+    assert_eq!(validate_order(&limit).unwrap_err().error_type, ErrorType::ValidationError);
+
+    limit.price = Some("150.25".to_string());
+    limit.time_in_force = TimeInForce::DayPlus;
+    assert!(validate_order(&limit).is_ok());
+
+    // Modes the API does not document are sent as given.
+    assert!(TimeInForce::from_str("gtc").is_err());
+    limit.time_in_force = serde_json::from_str(r#""gtc""#).unwrap();
+    assert_eq!(limit.time_in_force, TimeInForce::Unknown("gtc".to_string()));
+    assert!(validate_order(&limit).is_ok());
+
+    // Combinations are left to the API, only required fields are checked locally.
+    let mut market = order_params("AAPL", "100");
+    market.time_in_force = TimeInForce::AtClose;
+    market.price = Some("150.25".to_string());
+    assert!(validate_order(&market).is_ok());
+    market.price = Some("-1".to_string());
+    assert!(validate_order(&market).is_err());
+    market.price = None;
+
+    market.strategy = Strategy::Vwap {
+        start_at: None,
+        end_at: None,
+        urgency: None,
+        max_percent: Some(percent(10.0)),
+    };
+    assert!(validate_order(&market).is_ok());
+
+    market.strategy = Strategy::PercentOfVolume {
+        start_at: None,
+        end_at: None,
+        urgency: None,
+        target_percent: percent(20.0),
+        min_percent: Some(percent(5.0)),
+        max_percent: Some(percent(15.0)),
+    };
+    assert!(validate_order(&market).is_err());
+
+    for (order_type, price, stop_price) in [
+        (OrderType::Market, false, false),
+        (OrderType::Limit, true, false),
+        (OrderType::Stop, false, true),
+        (OrderType::StopLimit, true, true),
+        (OrderType::Unknown("pegged".to_string()), false, false),
+    ] {
+        assert_eq!(order_type.requires_price(), price);
+        assert_eq!(order_type.requires_stop_price(), stop_price);
+
+        let mut params = order_params("AAPL", "100");
+        params.order_type = order_type;
+        assert_eq!(validate_order(&params).is_ok(), !price && !stop_price);
+        params.price = Some("150.25".to_string());
+        params.stop_price = Some("149.00".to_string());
+        assert!(validate_order(&params).is_ok());
+    }
+
+    let zero = order_params("AAPL", "0");
+    assert!(validate_order(&zero).is_err());
+}
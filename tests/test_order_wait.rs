@@ -0,0 +1,116 @@
+mod common;
+
+use clearstreet::error::ErrorType;
+use clearstreet::orders::wait::{wait_for_status, wait_for_terminal};
+use clearstreet::orders::{OrderState, OrderStatus};
+use clearstreet::websockets::{ActivityBroadcaster, ActivityStream, BroadcastOptions};
+use common::mock::MockClient;
+use common::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn filled(order_id: &str, version: i64) -> clearstreet::orders::Order {
+    let mut filled = order(order_id, version, OrderStatus::Filled);
+    filled.state = OrderState::Closed;
+    filled.filled_quantity = "100".to_string();
+    filled
+}
+
+#[tokio::test]
+pub async fn test_wait_for_terminal_polls_without_feed() {
+    let client = Arc::new(MockClient::new());
+    client.set_order(order("order-1", 1, OrderStatus::New));
+
+    let background = client.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let done = filled("order-1", 2);
+        // The mock serves two trades per page, so the fill is only found on a later page.
+        background.add_trade(trade("trade-2", "order-2", "5", "150.00"));
+        background.add_trade(trade("trade-3", "order-2", "5", "150.00"));
+        background.add_trade(trade("trade-1", "order-1", "100", "150.00"));
+        background.set_order(done);
+    });
+
+    let completion = wait_for_terminal(client.as_ref(), None, "order-1", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(completion.order.status, OrderStatus::Filled);
+    assert_eq!(completion.fills.len(), 1);
+
+    client.set_order(order("order-3", 1, OrderStatus::New));
+    let error = wait_for_terminal(client.as_ref(), None, "order-3", Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::TimeoutError);
+}
+
+#[tokio::test]
+pub async fn test_fills_are_read_back_to_the_order_creation() {
+    let client = MockClient::new();
+    let done = filled("order-1", 2);
+
+    // Listed newest first, so older trades on later pages are not read.
+    let mut fill = trade("trade-1", "order-1", "100", "150.00");
+    fill.created_at = done.created_at + 1_000;
+    client.add_trade(fill);
+    for n in 2..=5 {
+        let mut older = trade(&format!("trade-{}", n), "order-0", "5", "150.00");
+        older.created_at = done.created_at - n * 1_000;
+        client.add_trade(older);
+    }
+    client.set_order(done);
+
+    let completion = wait_for_terminal(&client, None, "order-1", Duration::from_secs(1)).await.unwrap();
+    assert_eq!(completion.fills.len(), 1);
+    assert_eq!(*client.trade_pages.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+pub async fn test_wait_for_status_follows_feed() {
+    let client = MockClient::new();
+    client.set_order(order("order-1", 1, OrderStatus::New));
+
+    let (frames, stream) = frame_channel();
+    let feed = ActivityBroadcaster::new(ActivityStream::new(stream), BroadcastOptions::default());
+
+    let sender = frames.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Error notices do not name an order, so they do not end the wait.
+        sender
+            .send(r#"{"timestamp":1,"payload":{"type":"error-notice","details":"Order rejected by venue"}}"#.to_string())
+            .unwrap();
+        sender.send(trade_notice_frame(trade("trade-1", "order-1", "100", "150.00"), 1)).unwrap();
+        sender.send(order_update_frame(filled("order-1", 2), 2)).unwrap();
+    });
+
+    let completion = wait_for_status(
+        &client,
+        Some(&feed),
+        "order-1",
+        |order| order.status == OrderStatus::Filled,
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap();
+    assert_eq!(completion.order.version, 2);
+    assert_eq!(completion.fills[0].trade_id, "trade-1");
+
+    // A cancel can never become a fill.
+    client.set_order(order("order-2", 1, OrderStatus::New));
+    let mut canceled = order("order-2", 2, OrderStatus::Canceled);
+    canceled.state = OrderState::Closed;
+    frames.send(order_update_frame(canceled, 3)).unwrap();
+
+    let error = wait_for_status(
+        &client,
+        Some(&feed),
+        "order-2",
+        |order| order.status == OrderStatus::Filled,
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.error_type, ErrorType::OrderClosed);
+}
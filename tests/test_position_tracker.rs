@@ -0,0 +1,81 @@
+mod common;
+
+use clearstreet::orders::OrderSide;
+use clearstreet::positions::tracker::{PositionSource, PositionTracker};
+use common::mock::MockClient;
+use common::*;
+
+#[test]
+pub fn test_position_tracker_applies_updates_and_trades() {
+    let mut tracker = PositionTracker::new().derive_from_trades(true);
+    let mut changes = tracker.subscribe();
+
+    tracker.apply_position(&position("AAPL", "100", 150.0)).unwrap();
+    tracker.apply_position(&position("TSLA", "-10", 200.0)).unwrap();
+
+    let mut fill = trade("trade-1", "order-1", "100", "160");
+    fill.running_position = "200".to_string();
+    tracker.apply_trade(&fill).unwrap();
+
+    let aapl = tracker.get("AAPL").unwrap();
+    assert_eq!(aapl.quantity, 200.0);
+    assert_eq!(aapl.average_cost, 155.0);
+    assert_eq!(aapl.source, PositionSource::Trade);
+
+    let mut cover = trade("trade-2", "order-2", "10", "190");
+    cover.symbol = "TSLA".to_string();
+    cover.running_position = "0".to_string();
+    tracker.apply_trade(&cover).unwrap();
+    assert_eq!(tracker.quantity("TSLA"), 0.0);
+    assert_eq!(tracker.positions().len(), 1);
+
+    // The authoritative update replaces the derived position.
+    tracker.apply_position(&position("AAPL", "200", 155.5)).unwrap();
+    assert_eq!(tracker.get("AAPL").unwrap().source, PositionSource::PositionUpdate);
+
+    let exposure = tracker.exposure();
+    assert_eq!(exposure.long, 31_100.0);
+    assert_eq!(exposure.short, 0.0);
+    assert_eq!(exposure.net, 31_100.0);
+
+    let mut received = 0;
+    while changes.try_recv().is_ok() {
+        received += 1;
+    }
+    assert_eq!(received, 5);
+
+    let mut sell = trade("trade-3", "order-3", "50", "150");
+    sell.side = OrderSide::Sell;
+    sell.running_position = "150".to_string();
+    tracker.apply_trade(&sell).unwrap();
+    assert_eq!(tracker.average_cost("AAPL"), Some(155.5));
+
+    assert!(tracker.apply_position(&position("AAPL", "abc", 1.0)).is_err());
+}
+
+#[tokio::test]
+pub async fn test_seed_reads_every_page_and_reports_removals() {
+    let client = MockClient::new();
+    *client.positions.lock().unwrap() = vec![
+        position("AAPL", "100", 150.0),
+        position("TSLA", "-10", 200.0),
+        position("MSFT", "5", 400.0),
+    ];
+
+    let mut tracker = PositionTracker::new();
+    assert_eq!(tracker.seed(&client).await.unwrap(), 3);
+    assert_eq!(tracker.quantity("MSFT"), 5.0);
+
+    client.positions.lock().unwrap().retain(|p| p.symbol != "TSLA");
+    let mut changes = tracker.subscribe();
+    assert_eq!(tracker.seed(&client).await.unwrap(), 2);
+    assert!(tracker.get("TSLA").is_none());
+
+    let mut removed = Vec::new();
+    while let Ok(change) = changes.try_recv() {
+        if change.current.is_flat() {
+            removed.push((change.current.symbol, change.previous.unwrap().quantity));
+        }
+    }
+    assert_eq!(removed, vec![("TSLA".to_string(), -10.0)]);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use clearstreet::orders::OrderStatus;
+use clearstreet::websockets::recorder::{RecordedFrame, RecordingStream};
+use clearstreet::websockets::{ActivityMessage, ActivityStream, ReplaySpeed, SessionRecorder, SessionReplayer};
+use common::*;
+use futures_util::StreamExt;
+
+#[tokio::test]
+pub async fn test_record_and_replay() {
+    let path = std::env::temp_dir().join(format!("clearstreet-session-{}.jsonl", now()));
+
+    let (frames, stream) = frame_channel();
+    frames.send(ack_frame(true)).unwrap();
+    frames.send(order_update_frame(order("order-1", 1, OrderStatus::New), 1)).unwrap();
+    frames.send(replay_complete_frame()).unwrap();
+    drop(frames);
+
+    let recorder = SessionRecorder::create(&path).unwrap();
+    let mut live = ActivityStream::new(RecordingStream::new(stream, recorder));
+    let mut live_types = Vec::new();
+    while let Some(message) = live.next().await {
+        live_types.push(message.unwrap().payload_type());
+    }
+    let (_, recorder) = live.into_inner().into_parts();
+    recorder.finish().unwrap();
+
+    let replayer = SessionReplayer::open(&path).unwrap();
+    assert_eq!(replayer.frames().len(), 3);
+
+    let mut replayed = replayer.clone().into_activity_stream(ReplaySpeed::AsFastAsPossible);
+    let mut replayed_types = Vec::new();
+    while let Some(message) = replayed.next().await {
+        replayed_types.push(message.unwrap().payload_type());
+    }
+    assert_eq!(live_types, replayed_types);
+    assert!(replayed.is_replay_complete());
+
+    let blocking: Vec<ActivityMessage> = replayer
+        .replay_blocking(ReplaySpeed::Accelerated(1000.0))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(blocking.len(), 3);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+pub fn test_replay_with_degenerate_speed() {
+    let frames = vec![
+        RecordedFrame { received_at: 0, frame: heartbeat_frame() },
+        RecordedFrame { received_at: i64::MAX, frame: heartbeat_frame() },
+    ];
+
+    for factor in [f64::MIN_POSITIVE, f64::NAN, -1.0] {
+        let speed = ReplaySpeed::Accelerated(factor);
+        let replayed = SessionReplayer::from_frames(frames.clone()).replay_blocking(speed).count();
+        assert_eq!(replayed, 2);
+    }
+}
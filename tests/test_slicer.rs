@@ -0,0 +1,137 @@
+mod common;
+
+use clearstreet::emulation::slicer::{SliceSchedule, Slicer, SlicerEvent, SlicerOptions, SlicerStatus};
+use clearstreet::orders::create::CreateOrderParams;
+use clearstreet::orders::strategy::{Destination, Strategy};
+use clearstreet::orders::{OrderState, OrderStatus};
+use common::mock::MockClient;
+use common::*;
+
+const START: i64 = 1_700_000_000_000;
+
+fn dma_params(quantity: &str) -> CreateOrderParams {
+    let mut params = order_params("AAPL", quantity);
+    params.strategy = Strategy::DirectMarketAccess {
+        destination: Destination::Arcx,
+    };
+    params
+}
+
+fn created_quantities(client: &MockClient) -> Vec<String> {
+    client.created.lock().unwrap().iter().map(|params| params.quantity.clone()).collect()
+}
+
+async fn close(slicer: &mut Slicer, client: &MockClient, order_id: &str) {
+    let order = client.order(order_id).unwrap();
+    assert_eq!(order.state, OrderState::Closed);
+    slicer.apply_order(client, &order).await;
+}
+
+#[tokio::test]
+pub async fn test_twap_slicer_catches_up_and_pauses() {
+    let client = MockClient::new();
+    let options = SlicerOptions::new(START, START + 4_000, SliceSchedule::Twap { slices: 4 });
+    let mut slicer = Slicer::new(dma_params("100"), options).unwrap();
+    let mut events = slicer.subscribe();
+
+    slicer.tick(&client, START - 1).await.unwrap();
+    assert_eq!(client.created_count(), 0);
+    slicer.tick(&client, START).await.unwrap();
+
+    // Only 10 of the first 25 fill. The unfilled 15 is resent once its cancel is confirmed.
+    slicer.apply_trade(&client, &trade("t-1", "mock-order-1", "10", "150")).await;
+    slicer.tick(&client, START + 1_000).await.unwrap();
+    close(&mut slicer, &client, "mock-order-1").await;
+    slicer.tick(&client, START + 1_100).await.unwrap();
+    assert_eq!(created_quantities(&client), vec!["25", "25", "15"]);
+
+    slicer.pause(&client).await.unwrap();
+    assert_eq!(*slicer.status(), SlicerStatus::Paused);
+    close(&mut slicer, &client, "mock-order-2").await;
+    close(&mut slicer, &client, "mock-order-3").await;
+    slicer.tick(&client, START + 2_000).await.unwrap();
+    assert_eq!(client.created_count(), 3);
+
+    slicer.resume();
+    slicer.tick(&client, START + 3_000).await.unwrap();
+    assert_eq!(created_quantities(&client)[3], "90");
+
+    slicer.apply_trade(&client, &trade("t-2", "mock-order-4", "90", "151")).await;
+    assert_eq!(*slicer.status(), SlicerStatus::Completed);
+    let progress = slicer.progress(START + 3_000);
+    assert_eq!(progress.filled_quantity, 100.0);
+    assert_eq!(progress.average_price, Some(150.9));
+
+    let mut placed = 0;
+    while let Ok(event) = events.try_recv() {
+        if let SlicerEvent::ChildPlaced { .. } = event {
+            placed += 1;
+        }
+    }
+    assert_eq!(placed, 4);
+}
+
+#[tokio::test]
+pub async fn test_volume_curve_without_catch_up_expires() {
+    let client = MockClient::new();
+    let options = SlicerOptions::new(START, START + 2_000, SliceSchedule::VolumeCurve(vec![1.0, 3.0])).catch_up(false);
+    let mut slicer = Slicer::new(dma_params("100"), options.clone()).unwrap();
+
+    slicer.tick(&client, START).await.unwrap();
+    let mut order = client.order("mock-order-1").unwrap();
+    order.version += 1;
+    order.state = OrderState::Closed;
+    order.status = OrderStatus::Expired;
+    client.set_order(order.clone());
+    slicer.apply_order(&client, &order).await;
+
+    // The first interval's quantity was already sent, so nothing more until the next one.
+    slicer.tick(&client, START + 500).await.unwrap();
+    slicer.tick(&client, START + 1_000).await.unwrap();
+    assert_eq!(created_quantities(&client), vec!["25", "75"]);
+
+    slicer.tick(&client, START + 2_000).await.unwrap();
+    assert_eq!(*slicer.status(), SlicerStatus::Running);
+    close(&mut slicer, &client, "mock-order-2").await;
+    slicer.tick(&client, START + 2_000).await.unwrap();
+    assert_eq!(*slicer.status(), SlicerStatus::Expired);
+
+    assert!(Slicer::new(order_params("AAPL", "100"), options).is_err());
+}
+
+#[tokio::test]
+pub async fn test_resync_reads_every_trade_page() {
+    let client = MockClient::new();
+    let options = SlicerOptions::new(START, START + 2_000, SliceSchedule::Twap { slices: 2 });
+    let mut slicer = Slicer::new(dma_params("100"), options).unwrap();
+    slicer.tick(&client, START).await.unwrap();
+
+    // The child's fill is on the third page.
+    for n in 1..=4 {
+        client.add_trade(trade(&format!("other-{}", n), "other-order", "10", "150"));
+    }
+    client.add_trade(trade("t-1", "mock-order-1", "50", "150"));
+    slicer.resync(&client).await.unwrap();
+    assert_eq!(slicer.filled_quantity(), 50.0);
+}
+
+#[tokio::test]
+pub async fn test_failed_cancel_keeps_slicer_running() {
+    let client = MockClient::new();
+    let options = SlicerOptions::new(START, START + 2_000, SliceSchedule::Twap { slices: 2 });
+    let mut slicer = Slicer::new(dma_params("100"), options).unwrap();
+    slicer.tick(&client, START).await.unwrap();
+
+    // The child cannot be canceled, so neither pause nor cancel changes the status.
+    let child = client.orders.lock().unwrap().remove("mock-order-1").unwrap();
+    let error = slicer.pause(&client).await.unwrap_err();
+    assert!(error.message.contains("mock-order-1"));
+    assert_eq!(*slicer.status(), SlicerStatus::Running);
+    assert!(slicer.cancel(&client).await.is_err());
+    assert_eq!(*slicer.status(), SlicerStatus::Running);
+
+    client.set_order(child);
+    slicer.cancel(&client).await.unwrap();
+    assert_eq!(*slicer.status(), SlicerStatus::Canceled);
+    assert_eq!(client.deleted.lock().unwrap().clone(), vec!["mock-order-1".to_string()]);
+}
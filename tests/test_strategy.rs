@@ -0,0 +1,86 @@
+mod common;
+
+use clearstreet::instruments::AssetClass;
+use clearstreet::orders::strategy::{Destination, Percent, Strategy, Urgency};
+use common::percent;
+use serde_json::json;
+
+#[test]
+pub fn test_algorithmic_strategies_round_trip() {
+    let pov = Strategy::PercentOfVolume {
+        start_at: Some(1_700_000_000_000),
+        end_at: None,
+        urgency: Some(Urgency::Passive),
+        target_percent: percent(10.0),
+        min_percent: None,
+        max_percent: Some(percent(15.0)),
+    };
+
+    let value = serde_json::to_value(&pov).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "pov",
+            "start_at": 1_700_000_000_000i64,
+            "urgency": "passive",
+            "target_percent": 10.0,
+            "max_percent": 15.0
+        })
+    );
+    assert_eq!(serde_json::from_value::<Strategy>(value).unwrap(), pov);
+
+    let vwap: Strategy = serde_json::from_value(json!({"type": "vwap", "end_at": null, "max_percent": 20.0})).unwrap();
+    assert_eq!(
+        vwap,
+        Strategy::Vwap {
+            start_at: None,
+            end_at: None,
+            urgency: None,
+            max_percent: Some(percent(20.0))
+        }
+    );
+    assert_eq!(serde_json::to_value(&vwap).unwrap()["type"], "vwap");
+
+    let unmodelled = json!({"type": "dark-sweep", "min_fill": 100});
+    let other: Strategy = serde_json::from_value(unmodelled.clone()).unwrap();
+    assert_eq!(other.strategy_type(), "dark-sweep");
+    assert_eq!(serde_json::to_value(&other).unwrap(), unmodelled);
+
+    // A key the SDK does not model yet does not stop a known strategy from decoding.
+    let vwap: Strategy =
+        serde_json::from_value(json!({"type": "vwap", "max_percent": 20.0, "min_fill": 100})).unwrap();
+    assert_eq!(
+        vwap,
+        Strategy::Vwap {
+            start_at: None,
+            end_at: None,
+            urgency: None,
+            max_percent: Some(percent(20.0))
+        }
+    );
+    assert!(serde_json::from_value::<Strategy>(json!({"type": "pov", "target_percent": "NaN"})).is_err());
+    assert!(Percent::new(f64::NAN).is_err());
+}
+
+#[test]
+pub fn test_destination_parsing_and_metadata() {
+    for destination in Destination::ALL {
+        assert_eq!(&destination.to_string().parse::<Destination>().unwrap(), destination);
+        assert_eq!(&destination.mic().parse::<Destination>().unwrap(), destination);
+        assert_eq!(destination.asset_classes().len(), 1);
+    }
+
+    assert_eq!(Destination::Xcbo.mic(), "XCBO");
+    assert!(Destination::Xcbo.supports(&AssetClass::Option));
+    assert!(!Destination::Xnys.supports(&AssetClass::Option));
+
+    let dma: Strategy = serde_json::from_value(json!({"type": "dma", "destination": "newx"})).unwrap();
+    assert_eq!(
+        dma,
+        Strategy::DirectMarketAccess {
+            destination: Destination::Unknown("newx".to_string())
+        }
+    );
+    assert_eq!(serde_json::to_value(&dma).unwrap(), json!({"type": "dma", "destination": "newx"}));
+    assert!("".parse::<Destination>().is_err());
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use clearstreet::emulation::trailing_stop::{TrailOffset, TrailingStop, TrailingStopOptions};
+use clearstreet::error::ErrorType;
+use clearstreet::orders::{OrderSide, OrderStatus, OrderType};
+use common::mock::MockClient;
+use common::*;
+use std::time::Duration;
+
+#[tokio::test]
+pub async fn test_trailing_stop_follows_price_with_rate_limit() {
+    let client = MockClient::new();
+    let mut params = order_params("AAPL", "100");
+    params.order_side = OrderSide::Sell;
+
+    let options = TrailingStopOptions::new(TrailOffset::Absolute(2.0))
+        .min_step(0.10)
+        .min_interval(Duration::from_millis(50));
+    let mut stop = TrailingStop::place(&client, params, 150.0, options).await.unwrap();
+
+    let created = client.created.lock().unwrap()[0].clone();
+    assert_eq!(created.order_type, OrderType::Stop);
+    assert_eq!(created.stop_price.as_deref(), Some("148.00"));
+
+    // Falling prices and moves under the minimum step leave the stop alone.
+    assert_eq!(stop.on_price(&client, 149.0).await.unwrap(), None);
+    assert_eq!(stop.on_price(&client, 150.05).await.unwrap(), None);
+
+    assert_eq!(stop.on_price(&client, 151.0).await.unwrap(), Some(149.0));
+    // Held back by the rate limit, then applied from the best price seen.
+    assert_eq!(stop.on_price(&client, 153.0).await.unwrap(), None);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(stop.on_price(&client, 152.0).await.unwrap(), Some(151.0));
+
+    let updated = client.updated.lock().unwrap().clone();
+    assert_eq!(updated.len(), 2);
+    assert_eq!(updated[1].1.quantity, "100");
+    assert_eq!(updated[1].1.stop_price.as_deref(), Some("151.00"));
+
+    // A stop moved elsewhere is picked up, and trailing resumes from it.
+    let mut order = client.order(stop.order_id()).unwrap();
+    order.version += 1;
+    order.stop_price = Some("149.50".to_string());
+    stop.apply_order(&order);
+    assert_eq!(stop.stop_price(), 149.5);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(stop.on_price(&client, 152.0).await.unwrap(), Some(151.0));
+
+    // A fill means the stop triggered, so it stops trailing.
+    let mut order = client.order(stop.order_id()).unwrap();
+    order.version += 1;
+    order.status = OrderStatus::PartiallyFilled;
+    order.filled_quantity = "40".to_string();
+    stop.apply_order(&order);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(stop.is_done());
+    assert_eq!(stop.on_price(&client, 160.0).await.unwrap(), None);
+}
+
+#[tokio::test]
+pub async fn test_trailing_stop_percent_for_short() {
+    let client = MockClient::new();
+    let options = TrailingStopOptions::new(TrailOffset::Percent(1.0)).min_interval(Duration::ZERO);
+    let mut stop = TrailingStop::place(&client, order_params("AAPL", "10"), 100.0, options).await.unwrap();
+    assert_eq!(stop.stop_price(), 101.0);
+
+    assert_eq!(stop.on_price(&client, 90.0).await.unwrap(), Some(90.9));
+    assert_eq!(stop.on_price(&client, 95.0).await.unwrap(), None);
+    assert_eq!(stop.best_price(), 90.0);
+
+    assert!(TrailingStop::place(&client, order_params("AAPL", "10"), 100.0, TrailingStopOptions::new(TrailOffset::Percent(0.0)))
+        .await
+        .is_err());
+
+    // Parameters are validated before anything is placed.
+    let error = TrailingStop::place(&client, order_params("AAPL", "0"), 100.0, options).await.err().unwrap();
+    assert_eq!(error.error_type, ErrorType::ValidationError);
+    assert_eq!(client.created_count(), 1);
+}
@@ -0,0 +1,30 @@
+use clearstreet::orders::{Order, OrderStatus, OrderType, TimeInForce};
+use clearstreet::websockets::PayloadType;
+use serde_json::json;
+
+#[test]
+pub fn test_unknown_values_round_trip() {
+    let status: OrderStatus = serde_json::from_value(json!("pending-review")).unwrap();
+    assert_eq!(status, OrderStatus::Unknown("pending-review".to_string()));
+    assert_eq!(serde_json::to_value(&status).unwrap(), json!("pending-review"));
+    assert_eq!(status.to_string(), "pending-review");
+
+    for status in [OrderStatus::PartiallyFilled, OrderStatus::DoneForDay] {
+        let wire = serde_json::to_value(&status).unwrap();
+        assert_eq!(wire, json!(status.to_string()));
+        assert_eq!(status.to_string().parse::<OrderStatus>().unwrap(), status);
+    }
+
+    assert_eq!("stop-limit".parse::<OrderType>().unwrap(), OrderType::StopLimit);
+    assert_eq!(TimeInForce::ImmediateOrCancel.as_str(), "ioc");
+    // Only deserialization falls back to Unknown, parsing is strict.
+    assert!("algo-update".parse::<PayloadType>().is_err());
+    assert!(serde_json::from_value::<PayloadType>(json!("algo-update")).unwrap().is_unknown());
+    assert!("pending-review".parse::<OrderStatus>().is_err());
+
+    let mut order = serde_json::to_value(Order::default()).unwrap();
+    order["status"] = json!("pending-review");
+    order["time_in_force"] = json!("gtx");
+    let order: Order = serde_json::from_value(order).unwrap();
+    assert_eq!(order.time_in_force, TimeInForce::Unknown("gtx".to_string()));
+}